@@ -0,0 +1,108 @@
+//! Times each solver on mazes carved from a fixed seed and on the hand drawn
+//! boards in `fixtures/`, printing how long a path each one finds and how
+//! many squares it expands along the way.
+//!
+//! ```text
+//! cargo bench --no-default-features
+//! ```
+
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use pollen::{Algorithm, Maze, MazeGenerator, SquareKind};
+
+const SEED: u64 = 0x5eed;
+const SIZES: [i32; 3] = [21, 51, 99];
+
+const SOLVERS: [(&str, Algorithm); 4] = [
+    ("dfs", Algorithm::Dfs),
+    ("bfs", Algorithm::Bfs),
+    ("astar", Algorithm::AStar),
+    ("dijkstra", Algorithm::Dijkstra),
+];
+
+const FIXTURES: [(&str, &str); 3] = [
+    ("open", include_str!("fixtures/open.txt")),
+    ("rooms", include_str!("fixtures/rooms.txt")),
+    ("serpentine", include_str!("fixtures/serpentine.txt")),
+];
+
+struct Board {
+    name: String,
+    maze: Maze,
+    // a perfect maze has exactly one route between any two squares, so every
+    // solver has to find the same one
+    perfect: bool,
+}
+
+fn boards() -> Vec<Board> {
+    let generated = SIZES.iter().map(|&size| {
+        // endpoints in opposite corners so the path grows with the board
+        let mut maze = Maze::new(size, size);
+        maze.move_endpoint(1, 1, SquareKind::StartSquare);
+        maze.move_endpoint(size - 2, size - 2, SquareKind::EndSquare);
+        maze.generate(&MazeGenerator::Backtracker, Some(SEED));
+        Board {
+            name: format!("backtracker {}x{}", size, size),
+            maze,
+            perfect: true,
+        }
+    });
+
+    let fixtures = FIXTURES.iter().map(|(name, ascii)| Board {
+        name: name.to_string(),
+        maze: Maze::from_ascii(ascii).unwrap_or_else(|err| panic!("fixture {}: {}", name, err)),
+        perfect: false,
+    });
+
+    generated.chain(fixtures).collect()
+}
+
+// solves the board once with every solver, printing what each found and
+// checking they agree where they have to
+fn report(board: &Board) {
+    let mut shortest = None;
+    for (solver, algorithm) in SOLVERS.iter() {
+        let mut maze = board.maze.clone();
+        let path = maze.run(algorithm.clone());
+        assert!(maze.solved, "{} failed to solve {}", solver, board.name);
+        if let Err(err) = maze.check_path(&path) {
+            panic!(
+                "{} took an illegal path through {}: {}",
+                solver, board.name, err
+            );
+        }
+        println!(
+            "{}: {} path length {}, explored {}",
+            board.name, solver, maze.last_path_len, maze.last_explored
+        );
+
+        // dfs gives no guarantee about the length of its path, the others
+        // all find a shortest one on boards without mud
+        if board.perfect || *algorithm != Algorithm::Dfs {
+            let length = *shortest.get_or_insert(maze.last_path_len);
+            assert_eq!(
+                maze.last_path_len, length,
+                "{} found a different length path on {}",
+                solver, board.name
+            );
+        }
+    }
+}
+
+fn solvers(c: &mut Criterion) {
+    for board in boards() {
+        report(&board);
+
+        let mut group = c.benchmark_group(&board.name);
+        for (solver, algorithm) in SOLVERS.iter() {
+            group.bench_with_input(
+                BenchmarkId::from_parameter(solver),
+                &board.maze,
+                |b, maze| b.iter(|| maze.clone().run(algorithm.clone())),
+            );
+        }
+        group.finish();
+    }
+}
+
+criterion_group!(benches, solvers);
+criterion_main!(benches);
@@ -0,0 +1,192 @@
+use crate::{Maze, SquareKind, MAX_LOADED_DIMENSION};
+use std::fmt;
+
+// Boards as plain text: `#` for walls, `S` for the start, `E` for each end,
+// a digit for each portal, linked to the others with the same digit, a
+// letter from `a` on for each checkpoint in the order they're visited, and
+// spaces for open squares. Exported boards also mark the solution with `*`,
+// explored squares with `.`, or `,` when explored back from the end, squares
+// still waiting to be explored with `+` and filled dead ends with `~`, all of
+// which read back in as open squares.
+#[derive(Debug, PartialEq)]
+pub enum ParseError {
+    Empty,
+    // more than `MAX_LOADED_DIMENSION` rows or columns
+    TooLarge {
+        rows: usize,
+        columns: usize,
+    },
+    MissingStart,
+    MissingEnd,
+    DuplicateStart {
+        row: usize,
+        column: usize,
+    },
+    UnknownGlyph {
+        row: usize,
+        column: usize,
+        glyph: char,
+    },
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ParseError::Empty => write!(f, "maze is empty"),
+            ParseError::TooLarge { rows, columns } => write!(
+                f,
+                "maze is {} columns by {} rows, more than {} a side",
+                columns, rows, MAX_LOADED_DIMENSION
+            ),
+            ParseError::MissingStart => write!(f, "maze has no start square 'S'"),
+            ParseError::MissingEnd => write!(f, "maze has no end square 'E'"),
+            ParseError::DuplicateStart { row, column } => {
+                write!(f, "second start square at row {}, column {}", row, column)
+            }
+            ParseError::UnknownGlyph { row, column, glyph } => write!(
+                f,
+                "unknown character {:?} at row {}, column {}",
+                glyph, row, column
+            ),
+        }
+    }
+}
+
+impl Maze {
+    pub fn from_ascii(input: &str) -> Result<Maze, ParseError> {
+        let lines: Vec<&str> = input.lines().collect();
+        let width = lines
+            .iter()
+            .map(|line| line.chars().count())
+            .max()
+            .unwrap_or(0);
+        if width == 0 {
+            return Err(ParseError::Empty);
+        }
+        let limit = MAX_LOADED_DIMENSION as usize;
+        if width > limit || lines.len() > limit {
+            return Err(ParseError::TooLarge {
+                rows: lines.len(),
+                columns: width,
+            });
+        }
+
+        let mut squares = Vec::with_capacity(lines.len());
+        let (mut start, mut end) = (false, false);
+
+        for (row, line) in lines.iter().enumerate() {
+            // short lines are padded out with open squares
+            let mut squares_row = vec![SquareKind::Init; width];
+            for (column, glyph) in line.chars().enumerate() {
+                squares_row[column] = match glyph {
+                    '#' => SquareKind::Obstacle,
+                    ' ' | '.' | '+' | ',' | '~' | '*' => SquareKind::Init,
+                    'S' if start => return Err(ParseError::DuplicateStart { row, column }),
+                    'S' => {
+                        start = true;
+                        SquareKind::StartSquare
+                    }
+                    'E' => {
+                        end = true;
+                        SquareKind::EndSquare
+                    }
+                    '0'..='9' => SquareKind::Portal(glyph as u8 - b'0'),
+                    'a'..='z' => SquareKind::Checkpoint(glyph as u8 - b'a'),
+                    glyph => return Err(ParseError::UnknownGlyph { row, column, glyph }),
+                };
+            }
+            squares.push(squares_row);
+        }
+
+        if !start {
+            return Err(ParseError::MissingStart);
+        }
+        if !end {
+            return Err(ParseError::MissingEnd);
+        }
+
+        let mut maze = Maze::new(lines.len() as i32, width as i32);
+        maze.squares = squares;
+        Ok(maze)
+    }
+    pub fn to_ascii(&self) -> String {
+        let mut output = String::with_capacity(((self.width + 1) * self.height) as usize);
+        for row in self.squares.iter() {
+            for square_kind in row {
+                output.push(match square_kind {
+                    SquareKind::Init => ' ',
+                    SquareKind::Obstacle => '#',
+                    SquareKind::PossiblePath => '.',
+                    SquareKind::Frontier => '+',
+                    SquareKind::BackwardPath => ',',
+                    SquareKind::Filled => '~',
+                    SquareKind::SolutionPath => '*',
+                    SquareKind::StartSquare => 'S',
+                    SquareKind::EndSquare => 'E',
+                    // ids past nine can only come from a saved board, and
+                    // have no glyph that reads back in
+                    SquareKind::Portal(id) => char::from_digit(*id as u32, 10).unwrap_or('?'),
+                    SquareKind::Checkpoint(id) => checkpoint_glyph(*id).unwrap_or('?'),
+                });
+            }
+            output.push('\n');
+        }
+        output
+    }
+}
+
+/// The letter a checkpoint is written as, if its id has one.
+pub fn checkpoint_glyph(id: u8) -> Option<char> {
+    if id < 26 {
+        Some((b'a' + id) as char)
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reads_a_valid_maze() {
+        let maze = Maze::from_ascii("#####\n#S  #\n# # E\n##").unwrap();
+        assert_eq!((maze.width, maze.height), (5, 4));
+        assert_eq!(maze.get(1, 1), Some(&SquareKind::StartSquare));
+        assert_eq!(maze.get(4, 2), Some(&SquareKind::EndSquare));
+        assert_eq!(maze.get(2, 2), Some(&SquareKind::Obstacle));
+        // the short last line is padded out with open squares
+        assert_eq!(maze.get(4, 3), Some(&SquareKind::Init));
+    }
+
+    #[test]
+    fn rejects_a_maze_without_a_start() {
+        assert_eq!(
+            Maze::from_ascii("#  \n# E\n").err(),
+            Some(ParseError::MissingStart)
+        );
+    }
+
+    #[test]
+    fn rejects_an_unknown_glyph() {
+        assert_eq!(
+            Maze::from_ascii("S  \n ! E\n").err(),
+            Some(ParseError::UnknownGlyph {
+                row: 1,
+                column: 1,
+                glyph: '!'
+            })
+        );
+    }
+
+    #[test]
+    fn exports_the_solution_from_start_to_end() {
+        let mut maze = Maze::from_ascii("#######\n#S    #\n##### #\n#E    #\n#######\n").unwrap();
+        maze.traverse_bfs();
+
+        assert_eq!(
+            maze.to_ascii(),
+            "#######\n#S****#\n#####*#\n#E****#\n#######\n"
+        );
+    }
+}
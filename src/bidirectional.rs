@@ -0,0 +1,149 @@
+use std::collections::{HashMap, VecDeque};
+
+use crate::{reconstruct_path, Maze, SquareKind};
+
+// a breadth first search run forwards from the start and backwards from the
+// ends at once, taking turns to expand a square from each side until the two
+// meet somewhere in the middle
+#[derive(Clone)]
+pub(crate) struct Bidirectional {
+    start: (i32, i32),
+    forward: VecDeque<(i32, i32)>,
+    backward: VecDeque<(i32, i32)>,
+    // the square each side reached every other square from, with the squares
+    // each side set out from mapped to themselves
+    from_start: HashMap<(i32, i32), (i32, i32)>,
+    from_end: HashMap<(i32, i32), (i32, i32)>,
+    backward_turn: bool,
+}
+
+impl Maze {
+    pub fn traverse_bidirectional(&mut self) -> Vec<(i32, i32)> {
+        self.run(crate::Algorithm::Bidirectional)
+    }
+    pub(crate) fn begin_bidirectional(&mut self, start: (i32, i32), ends: &[(i32, i32)]) {
+        let mut search = Bidirectional {
+            start,
+            forward: VecDeque::new(),
+            backward: VecDeque::new(),
+            from_start: HashMap::new(),
+            from_end: HashMap::new(),
+            backward_turn: false,
+        };
+
+        search.from_start.insert(start, start);
+        search.forward.push_back(start);
+        for &end in ends {
+            search.from_end.insert(end, end);
+            search.backward.push_back(end);
+        }
+
+        self.bidirectional = Some(search);
+    }
+    pub(crate) fn step_bidirectional(&mut self) -> bool {
+        let mut search = match self.bidirectional.take() {
+            Some(search) => search,
+            None => return false,
+        };
+        let backward = search.backward_turn;
+        search.backward_turn = !backward;
+
+        let (queue, reached, other, kind) = if backward {
+            (
+                &mut search.backward,
+                &mut search.from_end,
+                &search.from_start,
+                SquareKind::BackwardPath,
+            )
+        } else {
+            (
+                &mut search.forward,
+                &mut search.from_start,
+                &search.from_end,
+                SquareKind::PossiblePath,
+            )
+        };
+
+        // once either side runs dry it has seen everything connected to it
+        // without meeting the other, so there's no path
+        let parent = match queue.pop_front() {
+            Some(parent) => parent,
+            None => {
+                self.unsolvable = true;
+                return false;
+            }
+        };
+        self.record_expansion(parent.0, parent.1);
+        self.mark_square(parent.0, parent.1, kind.clone());
+
+        for m in self.get_possible_moves(parent) {
+            let square = (m.0, m.1);
+
+            if other.contains_key(&square) {
+                reached.entry(square).or_insert(parent);
+                let path = join_halves(&search.from_start, &search.from_end, search.start, square);
+                self.mark_solution(&path);
+                return false;
+            }
+
+            if reached.contains_key(&square) {
+                continue;
+            }
+
+            self.mark_square(square.0, square.1, SquareKind::Frontier);
+            reached.insert(square, parent);
+            queue.push_back(square);
+        }
+
+        self.bidirectional = Some(search);
+        true
+    }
+}
+
+// joins the half running back to the start onto the half running back to an
+// end, sharing the square where they met, as a path from the start to the end
+fn join_halves(
+    from_start: &HashMap<(i32, i32), (i32, i32)>,
+    from_end: &HashMap<(i32, i32), (i32, i32)>,
+    start: (i32, i32),
+    meeting: (i32, i32),
+) -> Vec<(i32, i32)> {
+    let mut end = meeting;
+    while from_end[&end] != end {
+        end = from_end[&end];
+    }
+
+    let mut path = reconstruct_path(from_start, start, meeting);
+    path.extend(
+        reconstruct_path(from_end, end, meeting)
+            .into_iter()
+            .rev()
+            .skip(1),
+    );
+    path
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::MazeGenerator;
+
+    #[test]
+    fn joined_path_is_contiguous_and_shortest() {
+        for seed in 0..10 {
+            let mut maze = Maze::new(21, 21);
+            maze.generate(&MazeGenerator::Prim, Some(seed));
+            let mut bfs = maze.clone();
+
+            let path = maze.traverse_bidirectional();
+            assert!(maze.is_valid_path(&path), "seed {}", seed);
+            assert_eq!(path.len(), bfs.traverse_bfs().len(), "seed {}", seed);
+        }
+
+        let mut open = Maze::new(30, 30);
+        open.move_endpoint(25, 20, SquareKind::EndSquare);
+        let path = open.traverse_bidirectional();
+        assert!(open.is_valid_path(&path));
+        assert_eq!(path.len(), 40);
+    }
+}
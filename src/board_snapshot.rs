@@ -0,0 +1,97 @@
+use pollen::{Maze, SquareKind};
+use std::collections::VecDeque;
+
+// how many snapshots are kept before the oldest is dropped
+pub const SNAPSHOT_SLOTS: usize = 5;
+
+// the board as it was drawn when the snapshot was taken, leaving out
+// anything a search marked on it
+#[derive(Clone)]
+pub struct BoardSnapshot {
+    width: i32,
+    height: i32,
+    squares: Vec<Vec<SquareKind>>,
+    cost: Vec<Vec<u32>>,
+    locked: Vec<Vec<bool>>,
+}
+
+impl BoardSnapshot {
+    pub fn take(maze: &Maze) -> BoardSnapshot {
+        let mut drawn = maze.clone();
+        drawn.clear_solution();
+        BoardSnapshot {
+            width: drawn.width,
+            height: drawn.height,
+            squares: drawn.squares,
+            cost: drawn.cost,
+            locked: drawn.locked,
+        }
+    }
+    // puts the snapshot's size, squares, weights and locks back on `maze`,
+    // along with a clean slate for searching it. The movement rules are left
+    // as they are.
+    pub fn restore(&self, maze: &mut Maze) {
+        // set directly rather than through `resize`, which would clamp a
+        // loaded board's size to what the steppers allow
+        maze.width = self.width;
+        maze.height = self.height;
+        maze.reset_search();
+        maze.squares = self.squares.clone();
+        maze.cost = self.cost.clone();
+        maze.locked = self.locked.clone();
+    }
+}
+
+// the latest snapshots, newest last
+#[derive(Clone, Default)]
+pub struct Snapshots {
+    ring: VecDeque<BoardSnapshot>,
+}
+
+impl Snapshots {
+    pub fn push(&mut self, snapshot: BoardSnapshot) {
+        if self.ring.len() == SNAPSHOT_SLOTS {
+            self.ring.pop_front();
+        }
+        self.ring.push_back(snapshot);
+    }
+    pub fn latest(&self) -> Option<&BoardSnapshot> {
+        self.ring.back()
+    }
+    // drops the newest snapshot, so the one before it is restored next
+    pub fn discard(&mut self) {
+        self.ring.pop_back();
+    }
+    pub fn len(&self) -> usize {
+        self.ring.len()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pollen::Algorithm;
+
+    #[test]
+    fn restoring_puts_back_a_board_the_steppers_couldnt_size() {
+        // wider than `resize` allows and shorter than it allows
+        let text = format!("S{}E\n{}\n", " ".repeat(148), " ".repeat(150));
+        let mut maze = Maze::from_ascii(&text).unwrap();
+        maze.set_square(3, 1, SquareKind::Init, 4);
+        maze.set_locked(5, 0, true);
+        let snapshot = BoardSnapshot::take(&maze);
+
+        let mut edited = maze.clone();
+        edited.resize(20, 20);
+        edited.set(2, 2, SquareKind::Obstacle);
+        edited.run(Algorithm::Bfs);
+        snapshot.restore(&mut edited);
+
+        assert_eq!((edited.width, edited.height), (150, 2));
+        assert_eq!(edited.to_ascii(), maze.to_ascii());
+        assert_eq!(edited.cost, maze.cost);
+        assert_eq!(edited.locked, maze.locked);
+        assert_eq!(edited.dist.len(), 2);
+        assert!(edited.dist.iter().all(|row| row.len() == 150));
+    }
+}
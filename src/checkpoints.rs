@@ -0,0 +1,159 @@
+use crate::{Algorithm, Maze, SquareKind, ValidationError};
+use std::fmt;
+
+/// How many checkpoints a board can hold, one for each letter they're written
+/// as in ASCII boards.
+pub const MAX_CHECKPOINTS: u8 = 26;
+
+// what stops a route from being found through every checkpoint in turn
+#[derive(Debug, PartialEq)]
+pub enum CheckpointError {
+    Invalid(ValidationError),
+    // there's no way from `from` to the next checkpoint, or to an end on the
+    // last leg. Legs are counted from one.
+    Unsolvable { leg: usize, from: (i32, i32) },
+}
+
+impl fmt::Display for CheckpointError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            CheckpointError::Invalid(err) => write!(f, "{}", err),
+            CheckpointError::Unsolvable { leg, from } => {
+                write!(f, "leg {} from ({}, {}) has no path", leg, from.0, from.1)
+            }
+        }
+    }
+}
+
+impl std::error::Error for CheckpointError {}
+
+impl From<ValidationError> for CheckpointError {
+    fn from(err: ValidationError) -> CheckpointError {
+        CheckpointError::Invalid(err)
+    }
+}
+
+impl Maze {
+    /// Every checkpoint in the order a route has to visit them, lowest id
+    /// first. Checkpoints sharing an id are visited row by row.
+    pub fn checkpoints(&self) -> Vec<(i32, i32)> {
+        let mut checkpoints = vec![];
+        for (y, row) in self.squares.iter().enumerate() {
+            for (x, square_kind) in row.iter().enumerate() {
+                if let SquareKind::Checkpoint(id) = square_kind {
+                    checkpoints.push((*id, (x as i32, y as i32)));
+                }
+            }
+        }
+        checkpoints.sort_by_key(|(id, _)| *id);
+        checkpoints.into_iter().map(|(_, square)| square).collect()
+    }
+    /// Places the next checkpoint in order, or does nothing once all
+    /// `MAX_CHECKPOINTS` are placed.
+    pub fn add_checkpoint(&mut self, x: i32, y: i32) {
+        match self.get(x, y) {
+            Some(
+                SquareKind::StartSquare
+                | SquareKind::EndSquare
+                | SquareKind::Portal(_)
+                | SquareKind::Checkpoint(_),
+            )
+            | None => return,
+            Some(_) => {}
+        }
+
+        let next =
+            (0..MAX_CHECKPOINTS).find(|&id| self.find_square(SquareKind::Checkpoint(id)).is_none());
+        if let Some(id) = next {
+            self.set(x, y, SquareKind::Checkpoint(id));
+        }
+    }
+    /// Solves the board one leg at a time with `algorithm`, from the start to
+    /// each checkpoint in turn and on to whichever end is reached first,
+    /// returning the legs joined into one path. The squares each leg
+    /// explored are added up, so `last_explored` covers the whole route.
+    pub fn solve_checkpoints(
+        &mut self,
+        algorithm: Algorithm,
+    ) -> Result<Vec<(i32, i32)>, CheckpointError> {
+        self.clear_solution();
+        self.validate()?;
+
+        let start = self
+            .find_square(SquareKind::StartSquare)
+            .expect("a valid board has a start");
+        let targets = self.checkpoints().into_iter().map(Some).chain(Some(None));
+
+        let mut path = vec![start];
+        let mut explored = 0;
+        for (leg, target) in targets.enumerate() {
+            let from = *path.last().unwrap();
+            let mut maze = self.leg(from, target);
+            let leg_path = maze.run(algorithm.clone());
+            explored += maze.last_explored;
+
+            if !maze.solved {
+                self.unsolvable = true;
+                self.last_explored = explored;
+                return Err(CheckpointError::Unsolvable { leg: leg + 1, from });
+            }
+            // each leg starts where the one before it finished
+            path.extend(leg_path.into_iter().skip(1));
+        }
+
+        self.mark_solution(&path);
+        self.last_explored = explored;
+        Ok(path)
+    }
+    // a copy of the board starting from `from` and finishing at `to`, or at
+    // any of the board's own ends without one
+    fn leg(&self, from: (i32, i32), to: Option<(i32, i32)>) -> Maze {
+        let mut maze = self.clone();
+        maze.reset_search();
+        for square in maze.squares.iter_mut().flatten() {
+            match square {
+                SquareKind::StartSquare => *square = SquareKind::Init,
+                SquareKind::EndSquare if to.is_some() => *square = SquareKind::Init,
+                _ => {}
+            }
+        }
+
+        maze.set(from.0, from.1, SquareKind::StartSquare);
+        if let Some((x, y)) = to {
+            maze.set(x, y, SquareKind::EndSquare);
+        }
+        maze
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Algorithm, CheckpointError, Maze, SquareKind};
+
+    #[test]
+    fn the_path_goes_through_the_checkpoint_on_its_way_to_the_end() {
+        // the end is on the way to the checkpoint, so a path straight to it
+        // would leave the checkpoint out
+        let mut maze = Maze::from_ascii("S   \n### \n    \na  E\n").unwrap();
+        let path = maze.solve_checkpoints(Algorithm::Bfs).unwrap();
+        assert_eq!(path.first(), Some(&(0, 0)));
+        assert_eq!(path.last(), Some(&(3, 3)));
+        assert!(path.contains(&(0, 3)));
+        assert!(maze.check_path(&path).is_ok());
+        assert!(maze.solved);
+        assert_eq!(maze.get(0, 3), Some(&SquareKind::Checkpoint(0)));
+    }
+
+    #[test]
+    fn an_unsolvable_leg_is_named() {
+        let mut maze = Maze::from_ascii("S a\n###\nb E\n").unwrap();
+        assert_eq!(
+            maze.solve_checkpoints(Algorithm::AStar),
+            Err(CheckpointError::Unsolvable {
+                leg: 2,
+                from: (2, 0)
+            })
+        );
+        assert!(maze.unsolvable);
+    }
+}
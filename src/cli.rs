@@ -0,0 +1,137 @@
+use pollen::snapshot::compare_snapshot;
+use pollen::{Algorithm, Maze, SolveReport};
+use std::fs;
+use std::path::Path;
+use std::time::Instant;
+
+const USAGE: &str =
+    "usage: pollen solve <maze.txt> [--algo dfs|bfs|astar|dijkstra|greedy|iddfs|bidirectional|wall-follower] [--report <report.json>] [--expect <solved.txt>]";
+
+// exit codes, a maze without a solution is told apart from anything going wrong
+const UNSOLVABLE: i32 = 1;
+const FAILURE: i32 = 2;
+// the solved board isn't the one `--expect` names
+const MISMATCH: i32 = 3;
+
+// solves an ASCII maze without opening a window, returning the exit code
+pub fn run(args: &[String]) -> i32 {
+    let Args {
+        file,
+        algorithm,
+        report,
+        expect,
+    } = match parse_args(args) {
+        Ok(parsed) => parsed,
+        Err(err) => {
+            eprintln!("error: {}\n{}", err, USAGE);
+            return FAILURE;
+        }
+    };
+
+    let mut maze = match fs::read_to_string(&file)
+        .map_err(|err| err.to_string())
+        .and_then(|input| Maze::from_ascii(&input).map_err(|err| err.to_string()))
+    {
+        Ok(maze) => maze,
+        Err(err) => {
+            eprintln!("error: {}: {}", file, err);
+            return FAILURE;
+        }
+    };
+
+    let started = Instant::now();
+    maze.run(algorithm.clone());
+    let elapsed = started.elapsed();
+    print!("{}", maze.to_ascii());
+    println!("path length: {}", maze.last_path_len);
+    println!("explored: {}", maze.last_explored);
+
+    if let Some(report) = report {
+        if let Err(err) = SolveReport::new(&maze, algorithm, elapsed).save(Path::new(&report)) {
+            eprintln!("error: {}: {}", report, err);
+            return FAILURE;
+        }
+    }
+
+    if let Some(expect) = expect {
+        let expected = match fs::read_to_string(&expect) {
+            Ok(expected) => expected,
+            Err(err) => {
+                eprintln!("error: {}: {}", expect, err);
+                return FAILURE;
+            }
+        };
+        if let Err(err) = compare_snapshot(&maze.to_ascii(), &expected) {
+            eprintln!("error: {}: {}", expect, err);
+            return MISMATCH;
+        }
+    }
+
+    if maze.solved {
+        0
+    } else {
+        eprintln!("no path exists");
+        UNSOLVABLE
+    }
+}
+
+struct Args {
+    file: String,
+    algorithm: Algorithm,
+    // where to write a report, if anywhere
+    report: Option<String>,
+    // a solved board to check this one against
+    expect: Option<String>,
+}
+
+fn parse_args(args: &[String]) -> Result<Args, String> {
+    let mut args = args.iter();
+    match args.next().map(String::as_str) {
+        Some("solve") => {}
+        Some(command) => return Err(format!("unknown command {:?}", command)),
+        None => return Err("missing command".to_string()),
+    }
+
+    let mut file = None;
+    let mut algorithm = Algorithm::Bfs;
+    let mut report = None;
+    let mut expect = None;
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--algo" => {
+                let name = args.next().ok_or("--algo needs an algorithm")?;
+                algorithm = parse_algorithm(name)?;
+            }
+            "--report" => {
+                report = Some(args.next().ok_or("--report needs a file")?.clone());
+            }
+            "--expect" => {
+                expect = Some(args.next().ok_or("--expect needs a file")?.clone());
+            }
+            _ if file.is_none() => file = Some(arg.clone()),
+            _ => return Err(format!("unexpected argument {:?}", arg)),
+        }
+    }
+
+    let file = file.ok_or("missing maze file")?;
+    Ok(Args {
+        file,
+        algorithm,
+        report,
+        expect,
+    })
+}
+
+fn parse_algorithm(name: &str) -> Result<Algorithm, String> {
+    match name.to_lowercase().as_str() {
+        "dfs" => Ok(Algorithm::Dfs),
+        "bfs" => Ok(Algorithm::Bfs),
+        "astar" | "a*" => Ok(Algorithm::AStar),
+        "dijkstra" => Ok(Algorithm::Dijkstra),
+        "greedy" => Ok(Algorithm::Greedy),
+        "iddfs" => Ok(Algorithm::Iddfs),
+        "bidirectional" => Ok(Algorithm::Bidirectional),
+        "wall" | "wall-follower" => Ok(Algorithm::WallFollower),
+        _ => Err(format!("unknown algorithm {:?}", name)),
+    }
+}
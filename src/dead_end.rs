@@ -0,0 +1,124 @@
+use std::collections::hash_map::Entry;
+use std::collections::{HashMap, VecDeque};
+
+use crate::{reconstruct_path, Direction, Maze, SquareKind};
+
+impl Maze {
+    /// Solves the maze by filling in dead ends: any open square other than an
+    /// endpoint or a portal with only one open neighbour is filled, over and
+    /// over, until only the corridors joining the endpoints are left. Only
+    /// moves up, down, left and right count as neighbours, along with the
+    /// jumps between linked portals.
+    ///
+    /// This only leaves a single corridor on a perfect maze. A loop never has
+    /// a dead end to fill from, so on a board with loops they're left open
+    /// around the path that's returned.
+    pub fn solve_dead_end_fill(&mut self) -> Vec<(i32, i32)> {
+        self.clear_solution();
+
+        let start = match self.find_square(SquareKind::StartSquare) {
+            Some(start) => start,
+            None => {
+                self.unsolvable = true;
+                return vec![];
+            }
+        };
+
+        let mut dead_ends: Vec<(i32, i32)> = (0..self.height)
+            .flat_map(|y| (0..self.width).map(move |x| (x, y)))
+            .filter(|&square| self.is_dead_end(square))
+            .collect();
+
+        while let Some((x, y)) = dead_ends.pop() {
+            // a square can be queued again before it's filled
+            if !self.is_dead_end((x, y)) {
+                continue;
+            }
+            self.mark_square(x, y, SquareKind::Filled);
+            self.record_expansion(x, y);
+
+            // filling a square may leave the one it led to as a dead end
+            dead_ends.extend(
+                self.open_neighbours((x, y))
+                    .into_iter()
+                    .filter(|&square| self.is_dead_end(square)),
+            );
+        }
+
+        // walk what's left to find a path through it
+        let mut came_from = HashMap::from([(start, start)]);
+        let mut queue = VecDeque::from([start]);
+        while let Some(square) = queue.pop_front() {
+            if self.get(square.0, square.1) == Some(&SquareKind::EndSquare) {
+                let path = reconstruct_path(&came_from, start, square);
+                self.mark_solution(&path);
+                return path;
+            }
+            for next in self.open_neighbours(square) {
+                if let Entry::Vacant(entry) = came_from.entry(next) {
+                    entry.insert(square);
+                    queue.push_back(next);
+                }
+            }
+        }
+
+        self.unsolvable = true;
+        vec![]
+    }
+    fn is_dead_end(&self, (x, y): (i32, i32)) -> bool {
+        match self.get(x, y) {
+            Some(SquareKind::Obstacle)
+            | Some(SquareKind::Filled)
+            | Some(SquareKind::StartSquare)
+            | Some(SquareKind::EndSquare)
+            | Some(SquareKind::Portal(_))
+            | Some(SquareKind::Checkpoint(_))
+            | None => false,
+            Some(_) => self.open_neighbours((x, y)).len() <= 1,
+        }
+    }
+    // the squares next to this one that aren't walls or already filled, and
+    // the portals it jumps to if it's one itself
+    fn open_neighbours(&self, (x, y): (i32, i32)) -> Vec<(i32, i32)> {
+        Direction::all()
+            .map(Direction::delta)
+            .map(|(dx, dy)| self.wrapped(x + dx, y + dy))
+            .filter(|&(x, y)| {
+                !matches!(
+                    self.get(x, y),
+                    Some(SquareKind::Obstacle | SquareKind::Filled) | None
+                )
+            })
+            .chain(self.portal_partners((x, y)))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Maze, MazeGenerator, SquareKind};
+
+    #[test]
+    fn leaves_only_the_corridor_between_the_endpoints_of_a_perfect_maze() {
+        for seed in 0..5 {
+            // endpoints on the squares the generator carves between walls, so
+            // neither one opens up a loop
+            let mut maze = Maze::new(21, 21);
+            maze.move_endpoint(1, 1, SquareKind::StartSquare);
+            maze.move_endpoint(19, 19, SquareKind::EndSquare);
+            maze.generate(&MazeGenerator::Backtracker, Some(seed));
+            let shortest = maze.clone().traverse_bfs();
+
+            let path = maze.solve_dead_end_fill();
+            assert!(maze.solved, "seed {}", seed);
+            assert!(maze.is_valid_path(&path), "seed {}", seed);
+            assert_eq!(path.len(), shortest.len(), "seed {}", seed);
+            // everything off the corridor has been filled in
+            assert!(maze.find_squares(SquareKind::Init).is_empty());
+            assert_eq!(
+                maze.open_squares() - maze.find_squares(SquareKind::Filled).len(),
+                path.len()
+            );
+        }
+    }
+}
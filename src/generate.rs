@@ -0,0 +1,857 @@
+use crate::{init_cost, Maze, SquareKind};
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use rand::{Rng, SeedableRng};
+use std::collections::BTreeMap;
+
+/// How many boards `generate_solvable_random` scatters before clearing a
+/// path through the last one.
+pub const SOLVABLE_ATTEMPTS: u32 = 100;
+
+#[derive(Clone, PartialEq)]
+pub enum MazeGenerator {
+    Backtracker,
+    Prim,
+    Division,
+    Kruskal,
+    Eller,
+    HuntAndKill,
+    Wilson,
+}
+
+// Mazes are carved on a lattice of squares sharing the start square's parity,
+// with the squares in between acting as the walls that get knocked down.
+impl Maze {
+    // the same seed always carves the same maze on a board of the same size
+    // and endpoints, without one every maze is different
+    pub fn generate(&mut self, generator: &MazeGenerator, seed: Option<u64>) {
+        self.generate_with_progress(generator, seed, &mut |_| {});
+    }
+    /// Like `generate`, handing `progress` the share of the maze carved so
+    /// far as it goes, from 0.0 up to 1.0 once it's done.
+    pub fn generate_with_progress(
+        &mut self,
+        generator: &MazeGenerator,
+        seed: Option<u64>,
+        progress: &mut dyn FnMut(f64),
+    ) {
+        let rng = &mut seeded_rng(seed);
+        match generator {
+            MazeGenerator::Backtracker => self.carve_backtracker(rng, progress),
+            MazeGenerator::Prim => self.carve_prim(rng, progress),
+            MazeGenerator::Division => self.divide(rng, progress),
+            MazeGenerator::Kruskal => self.carve_kruskal(rng, progress),
+            MazeGenerator::Eller => self.carve_eller(rng, progress),
+            MazeGenerator::HuntAndKill => self.carve_hunt_and_kill(rng, progress),
+            MazeGenerator::Wilson => self.carve_wilson(rng, progress),
+        }
+    }
+    pub fn generate_maze(&mut self, seed: Option<u64>) {
+        self.carve_backtracker(&mut seeded_rng(seed), &mut |_| {});
+    }
+    pub fn generate_maze_prim(&mut self, seed: Option<u64>) {
+        self.carve_prim(&mut seeded_rng(seed), &mut |_| {});
+    }
+    pub fn generate_maze_recursive_division(&mut self, seed: Option<u64>) {
+        self.divide(&mut seeded_rng(seed), &mut |_| {});
+    }
+    pub fn generate_maze_kruskal(&mut self, seed: Option<u64>) {
+        self.carve_kruskal(&mut seeded_rng(seed), &mut |_| {});
+    }
+    pub fn generate_maze_eller(&mut self, seed: Option<u64>) {
+        self.carve_eller(&mut seeded_rng(seed), &mut |_| {});
+    }
+    pub fn generate_maze_hunt_and_kill(&mut self, seed: Option<u64>) {
+        self.carve_hunt_and_kill(&mut seeded_rng(seed), &mut |_| {});
+    }
+    pub fn generate_maze_wilson(&mut self, seed: Option<u64>) {
+        self.carve_wilson(&mut seeded_rng(seed), &mut |_| {});
+    }
+    pub fn carve_backtracker(&mut self, rng: &mut StdRng, progress: &mut dyn FnMut(f64)) {
+        let (start, ends) = match endpoints(self) {
+            Some(endpoints) => endpoints,
+            None => return,
+        };
+
+        let mut grid = walled_grid(self.height, self.width);
+        grid[start.1 as usize][start.0 as usize] = SquareKind::Init;
+        let mut carved = Carved::new(lattice_len(self, start), progress);
+        carved.add(1);
+
+        let mut stack = vec![start];
+        while let Some(&(x, y)) = stack.last() {
+            let unvisited: Vec<(i32, i32)> = lattice_neighbours(x, y, self.width, self.height)
+                .into_iter()
+                .filter(|&(nx, ny)| grid[ny as usize][nx as usize] == SquareKind::Obstacle)
+                .collect();
+
+            match unvisited.choose(rng) {
+                Some(&(nx, ny)) => {
+                    grid[((y + ny) / 2) as usize][((x + nx) / 2) as usize] = SquareKind::Init;
+                    grid[ny as usize][nx as usize] = SquareKind::Init;
+                    carved.add(1);
+                    stack.push((nx, ny));
+                }
+                None => {
+                    stack.pop();
+                }
+            }
+        }
+
+        self.finish_maze(grid, start, &ends);
+        progress(1.0);
+    }
+    pub fn carve_prim(&mut self, rng: &mut StdRng, progress: &mut dyn FnMut(f64)) {
+        let (start, ends) = match endpoints(self) {
+            Some(endpoints) => endpoints,
+            None => return,
+        };
+
+        let mut grid = walled_grid(self.height, self.width);
+        grid[start.1 as usize][start.0 as usize] = SquareKind::Init;
+        let mut carved = Carved::new(lattice_len(self, start), progress);
+        carved.add(1);
+
+        // each frontier entry is a wall along with the lattice square behind it
+        let mut frontier: Vec<((i32, i32), (i32, i32))> = vec![];
+        let add_walls = |frontier: &mut Vec<_>, (x, y): (i32, i32)| {
+            for (nx, ny) in lattice_neighbours(x, y, self.width, self.height) {
+                frontier.push((((x + nx) / 2, (y + ny) / 2), (nx, ny)));
+            }
+        };
+        add_walls(&mut frontier, start);
+
+        while !frontier.is_empty() {
+            let index = rng.gen_range(0..frontier.len());
+            let ((wall_x, wall_y), (x, y)) = frontier.swap_remove(index);
+
+            if grid[y as usize][x as usize] != SquareKind::Obstacle {
+                continue;
+            }
+
+            grid[wall_y as usize][wall_x as usize] = SquareKind::Init;
+            grid[y as usize][x as usize] = SquareKind::Init;
+            carved.add(1);
+            add_walls(&mut frontier, (x, y));
+        }
+
+        self.finish_maze(grid, start, &ends);
+        progress(1.0);
+    }
+    // walks at random from the start, carving as it goes, until it runs out
+    // of uncarved neighbours. It then hunts from the top of the board for an
+    // uncarved square next to a carved one, joins the two and walks on from
+    // there, without keeping a stack of where it has been.
+    pub fn carve_hunt_and_kill(&mut self, rng: &mut StdRng, progress: &mut dyn FnMut(f64)) {
+        let (start, ends) = match endpoints(self) {
+            Some(endpoints) => endpoints,
+            None => return,
+        };
+
+        let mut grid = walled_grid(self.height, self.width);
+        let mut carved = Carved::new(lattice_len(self, start), progress);
+        let (width, height) = (self.width, self.height);
+        let neighbours = |grid: &Vec<Vec<SquareKind>>, (x, y): (i32, i32), carved: bool| {
+            lattice_neighbours(x, y, width, height)
+                .into_iter()
+                .filter(|&(nx, ny)| (grid[ny as usize][nx as usize] == SquareKind::Init) == carved)
+                .collect::<Vec<_>>()
+        };
+        let carve = |grid: &mut Vec<Vec<SquareKind>>, (x, y): (i32, i32), (nx, ny): (i32, i32)| {
+            grid[((y + ny) / 2) as usize][((x + nx) / 2) as usize] = SquareKind::Init;
+            grid[ny as usize][nx as usize] = SquareKind::Init;
+        };
+
+        grid[start.1 as usize][start.0 as usize] = SquareKind::Init;
+        carved.add(1);
+        let mut current = Some(start);
+        while let Some(square) = current {
+            if let Some(&next) = neighbours(&grid, square, false).choose(rng) {
+                carve(&mut grid, square, next);
+                carved.add(1);
+                current = Some(next);
+                continue;
+            }
+
+            current = None;
+            let rows = (start.1.rem_euclid(2)..height).step_by(2);
+            'hunt: for y in rows {
+                for x in (start.0.rem_euclid(2)..width).step_by(2) {
+                    if grid[y as usize][x as usize] == SquareKind::Init {
+                        continue;
+                    }
+                    if let Some(&joined) = neighbours(&grid, (x, y), true).choose(rng) {
+                        carve(&mut grid, joined, (x, y));
+                        carved.add(1);
+                        current = Some((x, y));
+                        break 'hunt;
+                    }
+                }
+            }
+        }
+
+        self.finish_maze(grid, start, &ends);
+        progress(1.0);
+    }
+    // grows the maze from the start one loop-erased random walk at a time.
+    // Each walk sets off from a square outside the maze and wanders until it
+    // hits the maze, then the route it took, minus any loops, is carved in.
+    // Every perfect maze on the lattice is equally likely, so it has none of
+    // the other generators' bias towards long or short corridors.
+    pub fn carve_wilson(&mut self, rng: &mut StdRng, progress: &mut dyn FnMut(f64)) {
+        let (start, ends) = match endpoints(self) {
+            Some(endpoints) => endpoints,
+            None => return,
+        };
+
+        let mut grid = walled_grid(self.height, self.width);
+        let mut lattice = vec![];
+        for y in (start.1.rem_euclid(2)..self.height).step_by(2) {
+            for x in (start.0.rem_euclid(2)..self.width).step_by(2) {
+                lattice.push((x, y));
+            }
+        }
+        lattice.shuffle(rng);
+        let mut carved = Carved::new(lattice.len(), progress);
+
+        // the way the current walk last left each square. Going round a loop
+        // and leaving a square a second time overwrites the first exit, which
+        // is all it takes to erase the loop.
+        let mut exits: Vec<Vec<Option<(i32, i32)>>> =
+            vec![vec![None; self.width as usize]; self.height as usize];
+        let in_maze = |grid: &Vec<Vec<SquareKind>>, (x, y): (i32, i32)| {
+            grid[y as usize][x as usize] == SquareKind::Init
+        };
+
+        grid[start.1 as usize][start.0 as usize] = SquareKind::Init;
+        carved.add(1);
+        for &origin in &lattice {
+            if in_maze(&grid, origin) {
+                continue;
+            }
+
+            let mut square = origin;
+            while !in_maze(&grid, square) {
+                let next = *lattice_neighbours(square.0, square.1, self.width, self.height)
+                    .choose(rng)
+                    .expect("a lattice square always has a neighbour");
+                exits[square.1 as usize][square.0 as usize] = Some(next);
+                square = next;
+            }
+
+            let mut square = origin;
+            while !in_maze(&grid, square) {
+                let (x, y) = square;
+                let (nx, ny) = exits[y as usize][x as usize].unwrap();
+                grid[y as usize][x as usize] = SquareKind::Init;
+                grid[((y + ny) / 2) as usize][((x + nx) / 2) as usize] = SquareKind::Init;
+                carved.add(1);
+                square = (nx, ny);
+            }
+        }
+
+        self.finish_maze(grid, start, &ends);
+        progress(1.0);
+    }
+    // knocks down the walls between lattice squares in a random order,
+    // skipping any whose squares are already joined, so the corridors grow
+    // in many places at once instead of from the start
+    pub fn carve_kruskal(&mut self, rng: &mut StdRng, progress: &mut dyn FnMut(f64)) {
+        let (start, ends) = match endpoints(self) {
+            Some(endpoints) => endpoints,
+            None => return,
+        };
+
+        let mut grid = walled_grid(self.height, self.width);
+        let on_lattice = |x: i32, y: i32| (x - start.0) % 2 == 0 && (y - start.1) % 2 == 0;
+
+        // each wall is the pair of lattice squares either side of it
+        let mut walls = vec![];
+        for y in 0..self.height {
+            for x in 0..self.width {
+                if !on_lattice(x, y) {
+                    continue;
+                }
+                grid[y as usize][x as usize] = SquareKind::Init;
+                for (nx, ny) in [(x + 2, y), (x, y + 2)] {
+                    if nx < self.width && ny < self.height {
+                        walls.push(((x, y), (nx, ny)));
+                    }
+                }
+            }
+        }
+        walls.shuffle(rng);
+        // a perfect maze joins every lattice square to the rest once
+        let mut carved = Carved::new(lattice_len(self, start).saturating_sub(1), progress);
+
+        let mut sets = DisjointSets::new(self.square_count());
+        let index = |(x, y): (i32, i32)| y as usize * self.width as usize + x as usize;
+        for (a, b) in walls {
+            if sets.union(index(a), index(b)) {
+                grid[((a.1 + b.1) / 2) as usize][((a.0 + b.0) / 2) as usize] = SquareKind::Init;
+                carved.add(1);
+            }
+        }
+
+        self.finish_maze(grid, start, &ends);
+        progress(1.0);
+    }
+    // carves one lattice row at a time, remembering only which set each
+    // square on the current row belongs to. Squares in the same set are
+    // already joined by some route through the rows above.
+    pub fn carve_eller(&mut self, rng: &mut StdRng, progress: &mut dyn FnMut(f64)) {
+        let (start, ends) = match endpoints(self) {
+            Some(endpoints) => endpoints,
+            None => return,
+        };
+
+        let mut grid = walled_grid(self.height, self.width);
+        let columns: Vec<i32> = (start.0.rem_euclid(2)..self.width).step_by(2).collect();
+        let rows: Vec<i32> = (start.1.rem_euclid(2)..self.height).step_by(2).collect();
+
+        let mut carved = Carved::new(rows.len(), progress);
+
+        let mut sets: Vec<usize> = (0..columns.len()).collect();
+        let mut next_set = columns.len();
+        for (row, &y) in rows.iter().enumerate() {
+            for &x in &columns {
+                grid[y as usize][x as usize] = SquareKind::Init;
+            }
+
+            // join some neighbours that aren't connected yet, and on the last
+            // row all of them, since nothing below can join them later
+            let last = row + 1 == rows.len();
+            for i in 1..columns.len() {
+                if sets[i - 1] != sets[i] && (last || rng.gen_bool(0.5)) {
+                    let (from, to) = (sets[i], sets[i - 1]);
+                    for set in sets.iter_mut().filter(|set| **set == from) {
+                        *set = to;
+                    }
+                    grid[y as usize][(columns[i] - 1) as usize] = SquareKind::Init;
+                }
+            }
+            carved.add(1);
+            if last {
+                break;
+            }
+
+            // every set carries on into the next row at least once, or it
+            // would be cut off from everything below
+            let mut members: BTreeMap<usize, Vec<usize>> = BTreeMap::new();
+            for (i, &set) in sets.iter().enumerate() {
+                members.entry(set).or_default().push(i);
+            }
+            let mut below = vec![None; columns.len()];
+            for (set, mut squares) in members {
+                squares.shuffle(rng);
+                let count = rng.gen_range(1..=squares.len());
+                for &i in &squares[..count] {
+                    grid[(y + 1) as usize][columns[i] as usize] = SquareKind::Init;
+                    below[i] = Some(set);
+                }
+            }
+            sets = below
+                .into_iter()
+                .map(|set| {
+                    set.unwrap_or_else(|| {
+                        next_set += 1;
+                        next_set - 1
+                    })
+                })
+                .collect();
+        }
+
+        self.finish_maze(grid, start, &ends);
+        progress(1.0);
+    }
+    pub fn divide(&mut self, rng: &mut StdRng, progress: &mut dyn FnMut(f64)) {
+        let (start, ends) = match endpoints(self) {
+            Some(endpoints) => endpoints,
+            None => return,
+        };
+
+        let mut grid = vec![vec![SquareKind::Init; self.width as usize]; self.height as usize];
+        // a chamber too small to split any further is finished, and the
+        // share carved is how much of the board those cover
+        let mut carved = Carved::new(self.square_count(), progress);
+
+        // chambers are (left, top, right, bottom), inclusive. Walls only go on
+        // rows and columns off the lattice and gaps only on it, so later walls
+        // never seal off a gap left by an earlier one.
+        let mut chambers = vec![(0, 0, self.width - 1, self.height - 1)];
+        while let Some((left, top, right, bottom)) = chambers.pop() {
+            let rows: Vec<i32> = (top + 1..bottom)
+                .filter(|y| (y - start.1) % 2 != 0)
+                .collect();
+            let columns: Vec<i32> = (left + 1..right)
+                .filter(|x| (x - start.0) % 2 != 0)
+                .collect();
+
+            let (width, height) = (right - left, bottom - top);
+            let horizontal = match (rows.is_empty(), columns.is_empty()) {
+                (true, true) => {
+                    carved.add(((width + 1) * (height + 1)) as usize);
+                    continue;
+                }
+                (false, true) => true,
+                (true, false) => false,
+                _ if height != width => height > width,
+                _ => rng.gen_bool(0.5),
+            };
+
+            if horizontal {
+                let wall = *rows.choose(rng).unwrap();
+                let gaps: Vec<i32> = (left..=right).filter(|x| (x - start.0) % 2 == 0).collect();
+                let gap = *gaps.choose(rng).unwrap();
+                for x in left..=right {
+                    if x != gap {
+                        grid[wall as usize][x as usize] = SquareKind::Obstacle;
+                    }
+                }
+                chambers.push((left, top, right, wall - 1));
+                chambers.push((left, wall + 1, right, bottom));
+            } else {
+                let wall = *columns.choose(rng).unwrap();
+                let gaps: Vec<i32> = (top..=bottom).filter(|y| (y - start.1) % 2 == 0).collect();
+                let gap = *gaps.choose(rng).unwrap();
+                for y in top..=bottom {
+                    if y != gap {
+                        grid[y as usize][wall as usize] = SquareKind::Obstacle;
+                    }
+                }
+                chambers.push((left, top, wall - 1, bottom));
+                chambers.push((wall + 1, top, right, bottom));
+            }
+        }
+
+        self.finish_maze(grid, start, &ends);
+        progress(1.0);
+    }
+    /// Clears the board and walls in each square other than the endpoints
+    /// with probability `density`, independently of its neighbours.
+    pub fn random_fill(&mut self, density: f64, seed: Option<u64>) {
+        self.scatter(&mut seeded_rng(seed), density);
+    }
+    /// Like `random_fill`, but scatters again whenever the end can't be
+    /// reached, up to `attempts` times. Returns how many boards it scattered
+    /// to find one that can be solved, or `None` if none of them could.
+    pub fn random_fill_solvable(
+        &mut self,
+        density: f64,
+        seed: Option<u64>,
+        attempts: u32,
+    ) -> Option<u32> {
+        let mut rng = seeded_rng(seed);
+        for attempt in 1..=attempts.max(1) {
+            self.scatter(&mut rng, density);
+            self.traverse_bfs();
+            let solved = self.solved;
+            self.clear_solution();
+            if solved {
+                return Some(attempt);
+            }
+        }
+        None
+    }
+    /// Random fills until one can be solved, giving up after
+    /// `SOLVABLE_ATTEMPTS` boards and clearing a corridor from the start to
+    /// an end of the last one instead, through anything but locked walls.
+    /// Returns how many boards it scattered.
+    pub fn generate_solvable_random(&mut self, density: f64, seed: Option<u64>) -> u32 {
+        if let Some(attempts) = self.random_fill_solvable(density, seed, SOLVABLE_ATTEMPTS) {
+            return attempts;
+        }
+
+        if let Some((start, ends)) = endpoints(self) {
+            // across to the end's column, then down or up to its row
+            let (x, y) = ends[0];
+            let across = (start.0.min(x)..=start.0.max(x)).map(|column| (column, start.1));
+            let down = (start.1.min(y)..=start.1.max(y)).map(|row| (x, row));
+            for (column, row) in across.chain(down) {
+                if self.is_obstacle(column, row) && !self.is_locked(column, row) {
+                    self.set(column, row, SquareKind::Init);
+                }
+            }
+        }
+        SOLVABLE_ATTEMPTS
+    }
+    pub fn scatter(&mut self, rng: &mut StdRng, density: f64) {
+        let density = density.clamp(0.0, 1.0);
+        let (start, ends) = match endpoints(self) {
+            Some(endpoints) => endpoints,
+            None => return,
+        };
+
+        let mut grid = vec![vec![SquareKind::Init; self.width as usize]; self.height as usize];
+        for square in grid.iter_mut().flatten() {
+            if rng.gen_bool(density) {
+                *square = SquareKind::Obstacle;
+            }
+        }
+        grid[start.1 as usize][start.0 as usize] = SquareKind::StartSquare;
+        for &(x, y) in &ends {
+            grid[y as usize][x as usize] = SquareKind::EndSquare;
+        }
+
+        let mut cost = init_cost(self.height, self.width);
+        self.keep_locked(&mut grid, &mut cost);
+
+        self.reset_search();
+        self.squares = grid;
+        self.cost = cost;
+    }
+    fn finish_maze(
+        &mut self,
+        mut grid: Vec<Vec<SquareKind>>,
+        start: (i32, i32),
+        ends: &[(i32, i32)],
+    ) {
+        // an end can sit off the lattice, so carve a short corridor from it
+        // back onto the nearest lattice square to keep it reachable
+        for &(mut x, mut y) in ends {
+            if (x - start.0) % 2 != 0 {
+                grid[y as usize][x as usize] = SquareKind::Init;
+                x += if x > 0 { -1 } else { 1 };
+            }
+            if (y - start.1) % 2 != 0 {
+                grid[y as usize][x as usize] = SquareKind::Init;
+                y += if y > 0 { -1 } else { 1 };
+            }
+            grid[y as usize][x as usize] = SquareKind::Init;
+        }
+
+        grid[start.1 as usize][start.0 as usize] = SquareKind::StartSquare;
+        for &(x, y) in ends {
+            grid[y as usize][x as usize] = SquareKind::EndSquare;
+        }
+
+        let mut cost = init_cost(self.height, self.width);
+        self.keep_locked(&mut grid, &mut cost);
+
+        self.reset_search();
+        self.squares = grid;
+        self.cost = cost;
+    }
+}
+
+/// The squares whose kind differs between `a` and `b`, row by row. A square
+/// on only one of the boards, when they're different sizes, counts as
+/// differing.
+pub fn diff_grids(a: &Maze, b: &Maze) -> Vec<(i32, i32)> {
+    let mut differ = vec![];
+    for y in 0..a.height.max(b.height) {
+        for x in 0..a.width.max(b.width) {
+            if a.get(x, y) != b.get(x, y) {
+                differ.push((x, y));
+            }
+        }
+    }
+    differ
+}
+
+// a union-find over squares, telling which are already joined by a corridor
+struct DisjointSets {
+    parent: Vec<usize>,
+    rank: Vec<u8>,
+}
+
+impl DisjointSets {
+    fn new(len: usize) -> DisjointSets {
+        DisjointSets {
+            parent: (0..len).collect(),
+            rank: vec![0; len],
+        }
+    }
+    fn find(&mut self, mut item: usize) -> usize {
+        while self.parent[item] != item {
+            // point each square visited at its grandparent on the way up
+            self.parent[item] = self.parent[self.parent[item]];
+            item = self.parent[item];
+        }
+        item
+    }
+    // joins the sets holding `a` and `b`, returning false if they were
+    // already the same set
+    fn union(&mut self, a: usize, b: usize) -> bool {
+        let (a, b) = (self.find(a), self.find(b));
+        if a == b {
+            return false;
+        }
+
+        match self.rank[a].cmp(&self.rank[b]) {
+            std::cmp::Ordering::Less => self.parent[a] = b,
+            std::cmp::Ordering::Greater => self.parent[b] = a,
+            std::cmp::Ordering::Equal => {
+                self.parent[b] = a;
+                self.rank[a] += 1;
+            }
+        }
+        true
+    }
+}
+
+fn seeded_rng(seed: Option<u64>) -> StdRng {
+    match seed {
+        Some(seed) => StdRng::seed_from_u64(seed),
+        None => StdRng::from_entropy(),
+    }
+}
+
+// the start square followed by every end square
+type Endpoints = ((i32, i32), Vec<(i32, i32)>);
+
+fn endpoints(maze: &Maze) -> Option<Endpoints> {
+    let start = maze.find_square(SquareKind::StartSquare)?;
+    let ends = maze.find_squares(SquareKind::EndSquare);
+    if ends.is_empty() {
+        return None;
+    }
+    Some((start, ends))
+}
+
+// how many lattice squares there are on `maze` for a start at `start`
+fn lattice_len(maze: &Maze, start: (i32, i32)) -> usize {
+    let side = |from: i32, len: i32| ((len - from.rem_euclid(2) + 1) / 2).max(0) as usize;
+    side(start.0, maze.width) * side(start.1, maze.height)
+}
+
+// hands on the share of `total` squares carved so far, starting from none
+struct Carved<'a> {
+    done: usize,
+    total: usize,
+    progress: &'a mut dyn FnMut(f64),
+}
+
+impl<'a> Carved<'a> {
+    fn new(total: usize, progress: &'a mut dyn FnMut(f64)) -> Carved<'a> {
+        progress(0.0);
+        Carved {
+            done: 0,
+            total,
+            progress,
+        }
+    }
+    fn add(&mut self, squares: usize) {
+        self.done += squares;
+        (self.progress)((self.done as f64 / self.total.max(1) as f64).min(1.0));
+    }
+}
+
+fn walled_grid(height: i32, width: i32) -> Vec<Vec<SquareKind>> {
+    vec![vec![SquareKind::Obstacle; width as usize]; height as usize]
+}
+
+// the lattice squares two steps away in each direction that are on the board
+fn lattice_neighbours(x: i32, y: i32, width: i32, height: i32) -> Vec<(i32, i32)> {
+    vec![(x + 2, y), (x - 2, y), (x, y - 2), (x, y + 2)]
+        .into_iter()
+        .filter(|&(nx, ny)| nx >= 0 && nx < width && ny >= 0 && ny < height)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn backtracker_mazes_are_solvable() {
+        for seed in 0..20 {
+            for &(height, width) in &[(9, 10), (3, 3), (4, 7), (21, 21)] {
+                let mut maze = Maze::new(height, width);
+                maze.generate_maze(Some(seed));
+                assert!(!maze.traverse_bfs().is_empty(), "seed {}", seed);
+            }
+        }
+    }
+
+    // whether the start can reach every square that isn't a wall, which it
+    // doesn't count itself among
+    fn fully_connected(maze: &mut Maze) -> bool {
+        maze.flood_fill_from_start() + 1 == maze.open_squares()
+    }
+
+    #[test]
+    fn prim_corridors_are_connected() {
+        for seed in 0..20 {
+            for &(height, width) in &[(9, 10), (3, 3), (4, 7), (21, 21)] {
+                let mut maze = Maze::new(height, width);
+                maze.generate(&MazeGenerator::Prim, Some(seed));
+                assert!(fully_connected(&mut maze), "seed {}", seed);
+            }
+        }
+    }
+
+    #[test]
+    fn division_is_stable_for_a_seed() {
+        let mut maze = Maze::new(11, 11);
+        maze.generate(&MazeGenerator::Division, Some(7));
+
+        // a change to how chambers are split shows up as a different board
+        let expected = concat!(
+            "  # # # #  \n",
+            "    #      \n",
+            "### # ### #\n",
+            "      #    \n",
+            "# #########\n",
+            " S    # E  \n",
+            "# # ### # #\n",
+            "  #   #    \n",
+            "##### # ###\n",
+            "           \n",
+            "  # # # #  \n",
+        );
+        assert_eq!(maze.to_ascii(), expected);
+    }
+
+    #[test]
+    fn seeds_repeat_their_mazes() {
+        for generator in &[
+            MazeGenerator::Backtracker,
+            MazeGenerator::Prim,
+            MazeGenerator::Division,
+        ] {
+            let carve = |seed| {
+                let mut maze = Maze::new(21, 21);
+                maze.generate(generator, Some(seed));
+                maze.squares
+            };
+            assert!(carve(7) == carve(7));
+            assert!(carve(7) != carve(8));
+        }
+    }
+
+    #[test]
+    fn random_fill_density_runs_from_open_to_solid() {
+        let mut maze = Maze::new(20, 20);
+        maze.random_fill(0.0, Some(1));
+        assert!(maze.find_squares(SquareKind::Obstacle).is_empty());
+
+        maze.random_fill(1.0, Some(1));
+        assert_eq!(maze.find_squares(SquareKind::Obstacle).len(), 20 * 20 - 2);
+        assert!(maze.find_square(SquareKind::StartSquare).is_some());
+        assert!(maze.find_square(SquareKind::EndSquare).is_some());
+    }
+
+    #[test]
+    fn kruskal_corridors_are_connected() {
+        for seed in 0..20 {
+            for &(height, width) in &[(9, 10), (3, 3), (4, 7), (21, 31)] {
+                let mut maze = Maze::new(height, width);
+                maze.generate(&MazeGenerator::Kruskal, Some(seed));
+                assert!(fully_connected(&mut maze), "seed {}", seed);
+            }
+        }
+    }
+
+    #[test]
+    fn eller_connects_a_long_thin_board() {
+        for &(height, width) in &[(2, 50), (50, 2)] {
+            for seed in 0..5 {
+                let mut maze = Maze::new(height, width);
+                maze.move_endpoint(0, 0, SquareKind::StartSquare);
+                maze.move_endpoint(width - 1, height - 1, SquareKind::EndSquare);
+                maze.generate(&MazeGenerator::Eller, Some(seed));
+                assert!(fully_connected(&mut maze), "seed {}", seed);
+                assert!(!maze.traverse_bfs().is_empty(), "seed {}", seed);
+            }
+        }
+    }
+
+    #[test]
+    fn hunt_and_kill_corridors_are_connected() {
+        for seed in 0..10 {
+            for &(height, width) in &[(9, 10), (20, 20), (21, 31), (50, 3)] {
+                let mut maze = Maze::new(height, width);
+                maze.generate(&MazeGenerator::HuntAndKill, Some(seed));
+                assert!(fully_connected(&mut maze), "seed {}", seed);
+            }
+        }
+    }
+
+    // how many pairs of side by side squares are both open, which is one
+    // fewer than the open squares exactly when they form a tree
+    fn corridor_links(maze: &Maze) -> usize {
+        let open = |x: i32, y: i32| !matches!(maze.get(x, y), Some(SquareKind::Obstacle) | None);
+        (0..maze.height)
+            .flat_map(|y| (0..maze.width).map(move |x| (x, y)))
+            .filter(|&(x, y)| open(x, y))
+            .map(|(x, y)| open(x + 1, y) as usize + open(x, y + 1) as usize)
+            .sum()
+    }
+
+    #[test]
+    fn wilson_mazes_are_perfect() {
+        for seed in 0..10 {
+            for &(height, width) in &[(3, 9), (11, 11), (21, 31)] {
+                // endpoints on squares the lattice carves, so neither adds a
+                // loop
+                let mut maze = Maze::new(height, width);
+                maze.move_endpoint(0, 0, SquareKind::StartSquare);
+                maze.move_endpoint(width - 1, height - 1, SquareKind::EndSquare);
+                maze.generate(&MazeGenerator::Wilson, Some(seed));
+
+                assert_eq!(
+                    corridor_links(&maze) + 1,
+                    maze.open_squares(),
+                    "seed {}",
+                    seed
+                );
+                assert!(fully_connected(&mut maze), "seed {}", seed);
+            }
+        }
+    }
+
+    #[test]
+    fn solvable_random_boards_always_have_a_path() {
+        for seed in 0..10 {
+            for &density in &[0.3, 0.6, 1.0] {
+                let mut maze = Maze::new(20, 20);
+                let attempts = maze.generate_solvable_random(density, Some(seed));
+                assert!((1..=SOLVABLE_ATTEMPTS).contains(&attempts));
+                assert!(!maze.traverse_bfs().is_empty(), "density {}", density);
+            }
+        }
+    }
+
+    #[test]
+    fn diff_grids_lists_the_squares_that_changed() {
+        let a = Maze::from_ascii("S #\n   \n  E\n").unwrap();
+        let b = Maze::from_ascii("S  \n # \n  E\n").unwrap();
+        assert_eq!(diff_grids(&a, &b), vec![(2, 0), (1, 1)]);
+        assert!(diff_grids(&a, &a).is_empty());
+
+        // the extra column only one board has differs throughout
+        let wider = Maze::from_ascii("S # \n    \n  E \n").unwrap();
+        assert_eq!(diff_grids(&a, &wider), vec![(3, 0), (3, 1), (3, 2)]);
+    }
+
+    #[test]
+    fn generation_progress_climbs_from_nothing_to_all() {
+        for generator in &[
+            MazeGenerator::Backtracker,
+            MazeGenerator::Prim,
+            MazeGenerator::Division,
+            MazeGenerator::Kruskal,
+            MazeGenerator::Eller,
+            MazeGenerator::HuntAndKill,
+            MazeGenerator::Wilson,
+        ] {
+            for &(height, width) in &[(9, 10), (3, 3), (4, 7), (21, 21)] {
+                let mut maze = Maze::new(height, width);
+                let mut reports = vec![];
+                maze.generate_with_progress(generator, Some(3), &mut |share| reports.push(share));
+
+                assert_eq!(reports.first(), Some(&0.0));
+                assert_eq!(reports.last(), Some(&1.0));
+                assert!(reports.windows(2).all(|pair| pair[0] <= pair[1]));
+                assert!(reports.len() > 2);
+                // the carving generators count every square they carve, so
+                // they get to the whole maze by themselves, and only once
+                if *generator != MazeGenerator::Division {
+                    let whole = reports.iter().filter(|&&share| share == 1.0).count();
+                    assert_eq!(whole, 2, "{}x{}", width, height);
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,101 @@
+use pollen::{Maze, SquareKind};
+
+// a square as it was before and after an edit, along with its cost
+#[derive(Clone)]
+struct Change {
+    x: i32,
+    y: i32,
+    old_kind: SquareKind,
+    new_kind: SquareKind,
+    old_cost: u32,
+    new_cost: u32,
+}
+
+// every square changed by a single click, so moving an endpoint comes back
+// in one step rather than leaving the board with two starts or none
+type Edit = Vec<Change>;
+
+#[derive(Clone, Default)]
+pub struct History {
+    undo: Vec<Edit>,
+    redo: Vec<Edit>,
+}
+
+impl History {
+    // applies `edit` to the maze and remembers what it changed
+    pub fn record(&mut self, maze: &mut Maze, edit: impl FnOnce(&mut Maze)) {
+        let (squares, cost) = (maze.squares.clone(), maze.cost.clone());
+        edit(maze);
+
+        let mut changes = vec![];
+        for (y, row) in squares.into_iter().enumerate() {
+            for (x, old_kind) in row.into_iter().enumerate() {
+                let (new_kind, new_cost) = (&maze.squares[y][x], maze.cost[y][x]);
+                if old_kind != *new_kind || cost[y][x] != new_cost {
+                    changes.push(Change {
+                        x: x as i32,
+                        y: y as i32,
+                        old_kind,
+                        new_kind: new_kind.clone(),
+                        old_cost: cost[y][x],
+                        new_cost,
+                    });
+                }
+            }
+        }
+
+        if !changes.is_empty() {
+            self.undo.push(changes);
+            self.redo.clear();
+        }
+    }
+    pub fn can_undo(&self) -> bool {
+        !self.undo.is_empty()
+    }
+    pub fn can_redo(&self) -> bool {
+        !self.redo.is_empty()
+    }
+    pub fn undo(&mut self, maze: &mut Maze) {
+        if let Some(edit) = self.undo.pop() {
+            for change in &edit {
+                maze.set_square(change.x, change.y, change.old_kind.clone(), change.old_cost);
+            }
+            self.redo.push(edit);
+        }
+    }
+    pub fn redo(&mut self, maze: &mut Maze) {
+        if let Some(edit) = self.redo.pop() {
+            for change in &edit {
+                maze.set_square(change.x, change.y, change.new_kind.clone(), change.new_cost);
+            }
+            self.undo.push(edit);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn undoing_two_of_three_walls_keeps_the_first() {
+        let mut maze = Maze::default();
+        let mut history = History::default();
+        for x in 3..6 {
+            history.record(&mut maze, |maze| {
+                maze.set(x, 2, SquareKind::Obstacle);
+            });
+        }
+
+        history.undo(&mut maze);
+        history.undo(&mut maze);
+        assert_eq!(maze.find_squares(SquareKind::Obstacle), vec![(3, 2)]);
+
+        history.redo(&mut maze);
+        assert_eq!(
+            maze.find_squares(SquareKind::Obstacle),
+            vec![(3, 2), (4, 2)]
+        );
+        assert!(history.can_redo());
+    }
+}
@@ -0,0 +1,133 @@
+use std::collections::HashMap;
+
+use crate::{reconstruct_path, Algorithm, Maze, SquareKind};
+
+// a square waiting to be expanded, with how many moves the route to it took
+// and the square it was reached from
+type Pending = ((i32, i32), usize, (i32, i32));
+
+// a depth first search that gives up on any route longer than `limit`,
+// starting over with a limit one higher each time it runs dry, so the first
+// end it reaches is as few moves away as breadth first search would find
+#[derive(Clone)]
+pub(crate) struct Iddfs {
+    start: (i32, i32),
+    limit: usize,
+    max_limit: usize,
+    stack: Vec<Pending>,
+    // the fewest moves each square has been reached in this time round. A
+    // square reached again by a shorter route is expanded again, since the
+    // squares past it may now fit within the limit.
+    depth: HashMap<(i32, i32), usize>,
+    came_from: HashMap<(i32, i32), (i32, i32)>,
+    // whether any route was cut short this time round, without which a
+    // higher limit can't reach anything new
+    pruned: bool,
+}
+
+impl Maze {
+    pub fn traverse_iddfs(&mut self) -> Vec<(i32, i32)> {
+        self.run(Algorithm::Iddfs)
+    }
+    pub(crate) fn begin_iddfs(&mut self, start: (i32, i32)) {
+        self.iddfs = Some(Iddfs {
+            start,
+            limit: 1,
+            max_limit: self.square_count(),
+            stack: vec![(start, 0, start)],
+            depth: HashMap::new(),
+            came_from: HashMap::new(),
+            pruned: false,
+        });
+    }
+    pub(crate) fn step_iddfs(&mut self) -> bool {
+        let mut search = match self.iddfs.take() {
+            Some(search) => search,
+            None => return false,
+        };
+
+        // squares already reached by a route at least as short are skipped
+        // without using up a step
+        while let Some((square, depth, parent)) = search.stack.pop() {
+            if matches!(search.depth.get(&square), Some(&best) if best <= depth) {
+                continue;
+            }
+            search.depth.insert(square, depth);
+            if square != search.start {
+                search.came_from.insert(square, parent);
+            }
+
+            let (x, y) = square;
+            if self.get(x, y) == Some(&SquareKind::EndSquare) {
+                let path = reconstruct_path(&search.came_from, search.start, square);
+                self.mark_solution(&path);
+                return false;
+            }
+
+            self.record_expansion(x, y);
+            self.dist[y as usize][x as usize] = Some(depth as f64);
+            self.mark_square(x, y, SquareKind::PossiblePath);
+
+            if depth == search.limit {
+                search.pruned = true;
+            } else {
+                for m in self.get_possible_moves(square) {
+                    if self.get(m.0, m.1) == Some(&SquareKind::Init) {
+                        self.mark_square(m.0, m.1, SquareKind::Frontier);
+                    }
+                    search.stack.push(((m.0, m.1), depth + 1, square));
+                }
+            }
+
+            self.iddfs = Some(search);
+            return true;
+        }
+
+        // every route within the limit has been tried
+        if !search.pruned || search.limit >= search.max_limit {
+            self.unsolvable = true;
+            return false;
+        }
+
+        // start over from the start with a higher limit, forgetting what
+        // this round marked
+        for square in self.squares.iter_mut().flatten() {
+            if let SquareKind::PossiblePath | SquareKind::Frontier = square {
+                *square = SquareKind::Init;
+            }
+        }
+        self.dist = vec![vec![None; self.width as usize]; self.height as usize];
+        search.limit += 1;
+        search.pruned = false;
+        search.depth.clear();
+        search.came_from.clear();
+        search.stack.push((search.start, 0, search.start));
+
+        self.iddfs = Some(search);
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::Maze;
+
+    #[test]
+    fn finds_a_path_as_short_as_bfs_where_there_are_several_routes() {
+        // round either side of the wall, one way longer than the other
+        let text = "      \n S## E\n  #   \n      \n";
+        let mut maze = Maze::from_ascii(text).unwrap();
+        let bfs = maze.traverse_bfs();
+        let iddfs = maze.traverse_iddfs();
+        assert!(maze.solved);
+        assert!(maze.is_valid_path(&iddfs));
+        assert_eq!(iddfs.len(), bfs.len());
+
+        for seed in 0..10 {
+            let mut maze = Maze::new(15, 15);
+            maze.random_fill(0.25, Some(seed));
+            let bfs = maze.traverse_bfs();
+            assert_eq!(maze.traverse_iddfs().len(), bfs.len(), "seed {}", seed);
+        }
+    }
+}
@@ -0,0 +1,159 @@
+use druid::piet::{FontFamily, Text, TextLayout, TextLayoutBuilder};
+use druid::widget::prelude::*;
+use druid::{Point, Rect, Vec2, WidgetPod};
+
+use crate::{format_distance, State};
+
+const TEXT_SIZE: f64 = 12.0;
+const PADDING: f64 = 4.0;
+// how far the box sits from the cursor, so it doesn't hide the square
+const OFFSET: Vec2 = Vec2::new(16.0, 16.0);
+
+// wraps the board and, while `inspect` is ticked, shows what's known about
+// the square under the mouse in a box next to the cursor
+pub struct Inspector<W> {
+    child: WidgetPod<State, W>,
+    cursor: Point,
+    hovered: Option<(i32, i32)>,
+}
+
+impl<W: Widget<State>> Inspector<W> {
+    pub fn new(child: W) -> Inspector<W> {
+        Inspector {
+            child: WidgetPod::new(child),
+            cursor: Point::ORIGIN,
+            hovered: None,
+        }
+    }
+}
+
+impl<W: Widget<State>> Widget<State> for Inspector<W> {
+    fn event(&mut self, ctx: &mut EventCtx, event: &Event, data: &mut State, env: &Env) {
+        if let Event::MouseMove(mouse) = event {
+            let hovered = hovered_square(mouse.pos, ctx.size(), data.maze.width, data.maze.height);
+            if data.inspect && (hovered.is_some() || self.hovered.is_some()) {
+                ctx.request_paint();
+            }
+            self.cursor = mouse.pos;
+            self.hovered = hovered;
+        }
+
+        self.child.event(ctx, event, data, env)
+    }
+
+    fn lifecycle(&mut self, ctx: &mut LifeCycleCtx, event: &LifeCycle, data: &State, env: &Env) {
+        if let LifeCycle::HotChanged(false) = event {
+            self.hovered = None;
+            ctx.request_paint();
+        }
+        self.child.lifecycle(ctx, event, data, env)
+    }
+
+    fn update(&mut self, ctx: &mut UpdateCtx, old_data: &State, data: &State, env: &Env) {
+        // a running search changes what's under a cursor that hasn't moved
+        let changed = !old_data.maze.same(&data.maze) && self.hovered.is_some();
+        if old_data.inspect != data.inspect || (data.inspect && changed) {
+            ctx.request_paint();
+        }
+        self.child.update(ctx, data, env)
+    }
+
+    fn layout(
+        &mut self,
+        ctx: &mut LayoutCtx,
+        bc: &BoxConstraints,
+        data: &State,
+        env: &Env,
+    ) -> Size {
+        let size = self.child.layout(ctx, bc, data, env);
+        self.child.set_origin(ctx, Point::ORIGIN);
+        size
+    }
+
+    fn paint(&mut self, ctx: &mut PaintCtx, data: &State, env: &Env) {
+        self.child.paint(ctx, data, env);
+
+        let (x, y) = match self.hovered {
+            Some(square) if data.inspect => square,
+            _ => return,
+        };
+        // the board may have shrunk under the cursor
+        let text = match describe(data, x, y) {
+            Some(text) => text,
+            None => return,
+        };
+
+        let layout = ctx
+            .text()
+            .new_text_layout(text)
+            .font(FontFamily::SYSTEM_UI, TEXT_SIZE)
+            .text_color(data.palette.text)
+            .build()
+            .unwrap();
+
+        // keep the box on the board, flipping it to the other side of the
+        // cursor near the right and bottom edges
+        let size = layout.size();
+        let (width, height) = (size.width + PADDING * 2.0, size.height + PADDING * 2.0);
+        let bounds = ctx.size();
+        let mut origin = self.cursor + OFFSET;
+        if origin.x + width > bounds.width {
+            origin.x = self.cursor.x - OFFSET.x - width;
+        }
+        if origin.y + height > bounds.height {
+            origin.y = self.cursor.y - OFFSET.y - height;
+        }
+
+        let tooltip = Rect::from_origin_size(origin, Size::new(width, height));
+        ctx.fill(tooltip, &data.palette.init);
+        ctx.stroke(tooltip, &data.palette.grid, 1.0);
+        ctx.draw_text(&layout, origin + Vec2::new(PADDING, PADDING));
+    }
+}
+
+// the square under `pos` on a board of `columns` by `rows` squares stretched
+// over `size`, if it's over the board at all
+fn hovered_square(pos: Point, size: Size, columns: i32, rows: i32) -> Option<(i32, i32)> {
+    if pos.x < 0.0 || pos.y < 0.0 || pos.x >= size.width || pos.y >= size.height {
+        return None;
+    }
+    let x = (pos.x / size.width * columns as f64) as i32;
+    let y = (pos.y / size.height * rows as f64) as i32;
+    Some((x.min(columns - 1), y.min(rows - 1)))
+}
+
+fn describe(data: &State, x: i32, y: i32) -> Option<String> {
+    let maze = &data.maze;
+    let mut text = format!("({}, {}) {:?}", x, y, maze.get(x, y)?);
+    if let Some(dist) = maze.dist[y as usize][x as usize] {
+        text += &format!("\ndistance from start: {}", format_distance(dist));
+    }
+    if maze.solved && maze.path.contains(&(x, y)) {
+        text += "\non the solution path";
+    }
+    Some(text)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn the_mouse_hovers_the_square_its_over() {
+        let size = Size::new(200.0, 100.0);
+        assert_eq!(
+            hovered_square(Point::new(0.0, 0.0), size, 10, 5),
+            Some((0, 0))
+        );
+        assert_eq!(
+            hovered_square(Point::new(45.0, 61.0), size, 10, 5),
+            Some((2, 3))
+        );
+        assert_eq!(
+            hovered_square(Point::new(199.9, 99.9), size, 10, 5),
+            Some((9, 4))
+        );
+        assert_eq!(hovered_square(Point::new(200.0, 50.0), size, 10, 5), None);
+        assert_eq!(hovered_square(Point::new(-1.0, 50.0), size, 10, 5), None);
+    }
+}
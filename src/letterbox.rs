@@ -0,0 +1,85 @@
+use druid::widget::prelude::*;
+use druid::{Point, Rect, WidgetPod};
+
+// the number of columns and rows on the board
+type Cells<T> = Box<dyn Fn(&T) -> (i32, i32)>;
+
+// lays its child out as the largest area that fits the board's columns and
+// rows as square cells, centred, and leaves the space around it unpainted so the window
+// background shows through. Without it the board stretches to whatever shape
+// the window is and its squares turn into rectangles.
+pub struct Letterbox<T, W> {
+    child: WidgetPod<T, W>,
+    cells: Cells<T>,
+}
+
+impl<T: Data, W: Widget<T>> Letterbox<T, W> {
+    pub fn new(child: W, cells: impl Fn(&T) -> (i32, i32) + 'static) -> Letterbox<T, W> {
+        Letterbox {
+            child: WidgetPod::new(child),
+            cells: Box::new(cells),
+        }
+    }
+}
+
+impl<T: Data, W: Widget<T>> Widget<T> for Letterbox<T, W> {
+    fn event(&mut self, ctx: &mut EventCtx, event: &Event, data: &mut T, env: &Env) {
+        self.child.event(ctx, event, data, env)
+    }
+
+    fn lifecycle(&mut self, ctx: &mut LifeCycleCtx, event: &LifeCycle, data: &T, env: &Env) {
+        self.child.lifecycle(ctx, event, data, env)
+    }
+
+    fn update(&mut self, ctx: &mut UpdateCtx, old_data: &T, data: &T, env: &Env) {
+        if (self.cells)(old_data) != (self.cells)(data) {
+            ctx.request_layout();
+        }
+        self.child.update(ctx, data, env)
+    }
+
+    fn layout(&mut self, ctx: &mut LayoutCtx, bc: &BoxConstraints, data: &T, env: &Env) -> Size {
+        let size = bc.max();
+        let (columns, rows) = (self.cells)(data);
+        let area = fit(size, columns, rows);
+
+        self.child
+            .layout(ctx, &BoxConstraints::tight(area.size()), data, env);
+        self.child.set_origin(ctx, area.origin());
+        size
+    }
+
+    fn paint(&mut self, ctx: &mut PaintCtx, data: &T, env: &Env) {
+        self.child.paint(ctx, data, env)
+    }
+}
+
+// the biggest rectangle of `columns` by `rows` square cells that fits in
+// `size`, centred along whichever side has room to spare
+fn fit(size: Size, columns: i32, rows: i32) -> Rect {
+    let (columns, rows) = (columns.max(1) as f64, rows.max(1) as f64);
+    let cell = (size.width / columns).min(size.height / rows);
+    let board = Size::new(cell * columns, cell * rows);
+    let origin = Point::new(
+        (size.width - board.width) / 2.0,
+        (size.height - board.height) / 2.0,
+    );
+    Rect::from_origin_size(origin, board)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn a_wide_window_centres_the_board_across() {
+        let area = fit(Size::new(800.0, 400.0), 10, 10);
+        assert_eq!(area, Rect::new(200.0, 0.0, 600.0, 400.0));
+    }
+
+    #[test]
+    fn a_tall_window_centres_the_board_down() {
+        let area = fit(Size::new(300.0, 900.0), 10, 20);
+        assert_eq!(area, Rect::new(0.0, 150.0, 300.0, 750.0));
+    }
+}
@@ -0,0 +1,1652 @@
+//! The maze model and solvers behind pollen, free of any GUI dependency.
+//!
+//! ```
+//! use pollen::Maze;
+//!
+//! let mut maze = Maze::from_ascii("S  #\n## #\nE   \n").unwrap();
+//! let path = maze.traverse_bfs();
+//!
+//! assert_eq!(path.len(), 7);
+//! assert_eq!(path.first(), Some(&(0, 0)));
+//! assert_eq!(path.last(), Some(&(0, 2)));
+//! assert!(maze.solved);
+//! ```
+
+use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
+use std::collections::{BinaryHeap, HashMap, VecDeque};
+use std::hash::Hash;
+
+pub mod ascii;
+mod bidirectional;
+pub mod checkpoints;
+mod dead_end;
+pub mod generate;
+mod iddfs;
+pub mod path;
+pub mod picture;
+pub mod report;
+mod simple_path;
+pub mod snapshot;
+pub mod spec;
+pub mod storage;
+mod turns;
+pub mod validate;
+mod wall_follower;
+
+use bidirectional::Bidirectional;
+use iddfs::Iddfs;
+use simple_path::SimplePath;
+use turns::TurnSearch;
+use wall_follower::WallFollower;
+
+pub use ascii::ParseError;
+pub use checkpoints::CheckpointError;
+pub use generate::{diff_grids, MazeGenerator};
+pub use path::{Direction, Heading, PathCount, PathError};
+pub use picture::PictureError;
+pub use report::SolveReport;
+pub use snapshot::SnapshotError;
+pub use spec::SpecError;
+pub use storage::{MazeFileError, SavedMaze};
+pub use validate::{DimensionError, ValidationError};
+
+pub const DEFAULT_HEIGHT: i32 = 9;
+pub const DEFAULT_WIDTH: i32 = 10;
+
+pub const DEFAULT_START_X: i32 = 1;
+pub const DEFAULT_START_Y: i32 = 5;
+
+pub const DEFAULT_END_X: i32 = 8;
+pub const DEFAULT_END_Y: i32 = 5;
+
+// bounds for the board dimensions, at least three squares wide so the
+// start and end never land on the same square
+pub const MIN_DIMENSION: i32 = 3;
+pub const MAX_DIMENSION: i32 = 100;
+
+// the most squares a side a board read in from text or a saved file can
+// have, so that counting and indexing its squares can't overflow
+pub const MAX_LOADED_DIMENSION: i32 = 1000;
+
+// cost of crossing an ordinary square
+pub const DEFAULT_COST: u32 = 1;
+
+// how many pairs of portals a board can hold, one for each digit they're
+// written as in ASCII boards
+pub const PORTAL_PAIRS: u8 = 10;
+
+// the order moves out of a square have always been listed in, which sends
+// depth first search right before anywhere else
+pub const DEFAULT_NEIGHBOUR_ORDER: [Direction; 4] = [
+    Direction::Right,
+    Direction::Left,
+    Direction::Up,
+    Direction::Down,
+];
+
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
+pub enum Algorithm {
+    Dfs,
+    Bfs,
+    AStar,
+    Dijkstra,
+    Greedy,
+    Iddfs,
+    Bidirectional,
+    WallFollower,
+}
+
+// which squares a depth first search won't step onto a second time
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
+pub enum RevisitPolicy {
+    // any square it has expanded before, so each is expanded at most once
+    GlobalVisited,
+    // only the squares on the route it's following. Every dead end is backed
+    // out of and may be tried again from another direction, which shows the
+    // backtracking off but can take exponentially long on open boards.
+    CurrentPathOnly,
+}
+
+/// How A* and greedy best first search estimate the cost left to reach an
+/// end. A* only promises the shortest path with an estimate that never
+/// overshoots, which depends on how the searcher is allowed to move.
+#[derive(Clone, Copy, PartialEq, Debug, Serialize, Deserialize)]
+pub enum Heuristic {
+    /// The cheapest route on an open board under the current movement rules,
+    /// which is the Manhattan distance without diagonals. Always admissible,
+    /// and the closest of them to the true cost.
+    Octile,
+    /// Straight moves only. Overshoots once diagonals are allowed and cost
+    /// less than two straight steps.
+    Manhattan,
+    /// The straight line distance. Overshoots once diagonals are allowed
+    /// and cost less than the square root of two.
+    Euclidean,
+    /// The longer of the two axes, as if every diagonal cost the same as a
+    /// straight step. Always admissible, but weak once diagonals cost more.
+    Chebyshev,
+    /// No estimate at all, which leaves A* expanding squares in the same
+    /// order as Dijkstra on an unweighted board.
+    Zero,
+}
+
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
+pub enum SquareKind {
+    Init,
+    Obstacle,
+    PossiblePath,
+    // waiting in a search's stack, queue or heap to be expanded
+    Frontier,
+    // explored by a bidirectional search on its way back from the end
+    BackwardPath,
+    // ruled out as a dead end by the dead end filler
+    Filled,
+    SolutionPath,
+    StartSquare,
+    EndSquare,
+    // linked to every other portal with the same id, so a search standing on
+    // one can jump straight to the others in a single move
+    Portal(u8),
+    // has to be passed through on the way to an end by `solve_checkpoints`,
+    // in order of id, and is an ordinary open square to every other solver
+    Checkpoint(u8),
+}
+
+#[derive(Clone, Debug)]
+// (x, y, previous position)
+struct Move(i32, i32, (i32, i32));
+
+// a move waiting in a frontier along with the cost of reaching it,
+// ordered so that the lowest priority is popped first from a BinaryHeap.
+// Equal priorities fall back on `tie`, lowest first by its first part and
+// then its second.
+#[derive(Clone)]
+struct Candidate {
+    priority: f64,
+    tie: (f64, f64),
+    cost: f64,
+    m: Move,
+}
+
+impl PartialEq for Candidate {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Candidate {}
+
+impl PartialOrd for Candidate {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Candidate {
+    fn cmp(&self, other: &Self) -> Ordering {
+        other
+            .priority
+            .total_cmp(&self.priority)
+            .then_with(|| other.tie.0.total_cmp(&self.tie.0))
+            .then_with(|| other.tie.1.total_cmp(&self.tie.1))
+    }
+}
+
+// the squares waiting to be expanded, kept in the order the algorithm visits them
+#[derive(Clone)]
+enum Frontier {
+    Stack(Vec<Candidate>),
+    Queue(VecDeque<Candidate>),
+    Heap(BinaryHeap<Candidate>),
+}
+
+impl Frontier {
+    fn new(algorithm: &Algorithm) -> Frontier {
+        match algorithm {
+            Algorithm::Dfs | Algorithm::Iddfs | Algorithm::WallFollower => Frontier::Stack(vec![]),
+            Algorithm::Bfs | Algorithm::Bidirectional => Frontier::Queue(VecDeque::new()),
+            Algorithm::AStar | Algorithm::Dijkstra | Algorithm::Greedy => {
+                Frontier::Heap(BinaryHeap::new())
+            }
+        }
+    }
+
+    fn push(&mut self, candidate: Candidate) {
+        match self {
+            Frontier::Stack(stack) => stack.push(candidate),
+            Frontier::Queue(queue) => queue.push_back(candidate),
+            Frontier::Heap(heap) => heap.push(candidate),
+        }
+    }
+
+    fn pop(&mut self) -> Option<Candidate> {
+        match self {
+            Frontier::Stack(stack) => stack.pop(),
+            Frontier::Queue(queue) => queue.pop_front(),
+            Frontier::Heap(heap) => heap.pop(),
+        }
+    }
+}
+
+// a traversal in progress, kept on the maze so it can be advanced one
+// expansion at a time
+#[derive(Clone)]
+struct Search {
+    algorithm: Algorithm,
+    // every end square, the search stops at whichever it reaches first
+    ends: Vec<(i32, i32)>,
+    frontier: Frontier,
+    visited: Vec<Vec<bool>>,
+    start: (i32, i32),
+    // the square each expanded square was reached from
+    came_from: HashMap<(i32, i32), (i32, i32)>,
+    // portals with somewhere to jump to, which the heuristic has to allow for
+    portals: Vec<(i32, i32)>,
+}
+
+/// Follows `came_from` back from `end` until it reaches `start`, returning the
+/// squares along the way from start to end. The path is empty when `end`
+/// can't be traced back to `start`. Searches that tell squares apart by more
+/// than where they are can key `came_from` by whatever else they track too.
+pub fn reconstruct_path<K: Copy + Eq + Hash>(
+    came_from: &HashMap<K, K>,
+    start: K,
+    end: K,
+) -> Vec<K> {
+    let mut path = vec![end];
+    let mut current = end;
+
+    while current != start {
+        // a chain longer than the map must have looped back on itself
+        match came_from.get(&current) {
+            Some(&previous) if path.len() <= came_from.len() => {
+                path.push(previous);
+                current = previous;
+            }
+            _ => return vec![],
+        }
+    }
+
+    path.reverse();
+    path
+}
+
+// how far `square` is from the straight line through `start` and `end`
+fn off_line_distance(square: (i32, i32), start: (i32, i32), end: (i32, i32)) -> f64 {
+    let (dx, dy) = ((end.0 - start.0) as f64, (end.1 - start.1) as f64);
+    let length = dx.hypot(dy);
+    if length == 0.0 {
+        return 0.0;
+    }
+    let (sx, sy) = ((square.0 - start.0) as f64, (square.1 - start.1) as f64);
+    (sx * dy - sy * dx).abs() / length
+}
+
+fn manhattan_distance(from: (i32, i32), to: (i32, i32)) -> i32 {
+    (from.0 - to.0).abs() + (from.1 - to.1).abs()
+}
+
+fn euclidean_distance(from: (i32, i32), to: (i32, i32)) -> f64 {
+    ((from.0 - to.0) as f64).hypot((from.1 - to.1) as f64)
+}
+
+fn chebyshev_distance(from: (i32, i32), to: (i32, i32)) -> i32 {
+    (from.0 - to.0).abs().max((from.1 - to.1).abs())
+}
+
+// the least a route between two squares can cost when a diagonal step costs
+// `diagonal` and a straight one costs 1. Each square both axes have to cover
+// takes at worst a diagonal step or two straight ones, and each left over
+// along the longer axis at worst a straight step or a zig-zagging diagonal.
+fn octile_distance(from: (i32, i32), to: (i32, i32), diagonal: f64) -> f64 {
+    let (dx, dy) = ((from.0 - to.0).abs(), (from.1 - to.1).abs());
+    let (short, long) = (dx.min(dy) as f64, dx.max(dy) as f64);
+    short * diagonal.min(2.0) + (long - short) * diagonal.min(1.0)
+}
+
+/// A board of squares along with the state of any search running over it.
+#[derive(Clone)]
+pub struct Maze {
+    pub width: i32,
+    pub height: i32,
+    pub solved: bool,
+    pub unsolvable: bool,
+    pub allow_diagonals: bool,
+    pub allow_corner_cutting: bool,
+    // moving off one edge of the board comes back on at the opposite edge
+    pub wrap: bool,
+    // what A* and Dijkstra charge for a diagonal step, relative to 1 for a
+    // straight one, so they don't zig-zag where a diagonal would do
+    pub diagonal_cost: f64,
+    // what A* and Dijkstra add each time the route changes direction, so a
+    // straighter route wins over one that zig-zags for the same distance
+    pub turn_cost: f64,
+    pub revisit_policy: RevisitPolicy,
+    pub heuristic: Heuristic,
+    // the order the straight moves out of a square are listed in, which depth
+    // first search tries first to last, ahead of any diagonals. Diagonals
+    // listed here are left to `allow_diagonals` instead.
+    pub neighbour_order: [Direction; 4],
+    // how long the last solution was and how many squares its search
+    // expanded, both left at zero until a search has run
+    pub last_path_len: usize,
+    pub last_explored: usize,
+    // every square the last search expanded, in the order it expanded them
+    pub expanded: Vec<(i32, i32)>,
+    pub path: Vec<(i32, i32)>,
+    pub squares: Vec<Vec<SquareKind>>,
+    // cost of moving onto each square, kept apart from `squares` so that
+    // painting a path over weighted terrain doesn't lose its weight
+    pub cost: Vec<Vec<u32>>,
+    // what it cost the last search to reach each square it expanded, counted
+    // in steps or by weight depending on the algorithm. The bidirectional
+    // search and the wall follower leave it empty.
+    pub dist: Vec<Vec<Option<f64>>>,
+    // where the last search set off from
+    pub search_start: Option<(i32, i32)>,
+    // squares the generators, fills and clears leave as they are
+    pub locked: Vec<Vec<bool>>,
+    search: Option<Search>,
+    bidirectional: Option<Bidirectional>,
+    wall_follower: Option<WallFollower>,
+    iddfs: Option<Iddfs>,
+    simple_path: Option<SimplePath>,
+    turns: Option<TurnSearch>,
+}
+
+impl Default for Maze {
+    fn default() -> Self {
+        Maze::new(DEFAULT_HEIGHT, DEFAULT_WIDTH)
+    }
+}
+
+impl Maze {
+    /// An empty board with the start and end in their default places.
+    pub fn new(height: i32, width: i32) -> Maze {
+        Maze {
+            width,
+            height,
+            solved: false,
+            unsolvable: false,
+            allow_diagonals: false,
+            allow_corner_cutting: false,
+            wrap: false,
+            diagonal_cost: std::f64::consts::SQRT_2,
+            turn_cost: 0.0,
+            revisit_policy: RevisitPolicy::GlobalVisited,
+            heuristic: Heuristic::Octile,
+            neighbour_order: DEFAULT_NEIGHBOUR_ORDER,
+            last_path_len: 0,
+            last_explored: 0,
+            expanded: vec![],
+            path: vec![],
+            squares: init_squares(height, width),
+            cost: init_cost(height, width),
+            dist: vec![vec![None; width as usize]; height as usize],
+            search_start: None,
+            locked: vec![vec![false; width as usize]; height as usize],
+            search: None,
+            bidirectional: None,
+            wall_follower: None,
+            iddfs: None,
+            simple_path: None,
+            turns: None,
+        }
+    }
+    pub fn clear(&mut self) {
+        let mut squares = init_squares(self.height, self.width);
+        let mut cost = init_cost(self.height, self.width);
+        self.keep_locked(&mut squares, &mut cost);
+
+        self.reset_search();
+        self.squares = squares;
+        self.cost = cost;
+    }
+    // knocks down every wall that isn't locked and wipes the last search,
+    // keeping the start, the ends and any weighted squares where they are
+    pub fn clear_walls(&mut self) {
+        self.clear_solution();
+        for (square, &locked) in self
+            .squares
+            .iter_mut()
+            .flatten()
+            .zip(self.locked.iter().flatten())
+        {
+            if *square == SquareKind::Obstacle && !locked {
+                *square = SquareKind::Init;
+            }
+        }
+    }
+    pub fn is_locked(&self, x: i32, y: i32) -> bool {
+        self.get(x, y).is_some() && self.locked[y as usize][x as usize]
+    }
+    /// Locks or unlocks a square. The start and end squares move around too
+    /// much to be locked, so they're left alone.
+    pub fn set_locked(&mut self, x: i32, y: i32, locked: bool) {
+        match self.get(x, y) {
+            Some(SquareKind::StartSquare | SquareKind::EndSquare) | None => {}
+            Some(_) => self.locked[y as usize][x as usize] = locked,
+        }
+    }
+    // copies the locked squares of the current board into a new one that's
+    // about to replace it, except where either board has an endpoint
+    pub(crate) fn keep_locked(&self, squares: &mut [Vec<SquareKind>], cost: &mut [Vec<u32>]) {
+        for (y, row) in self.locked.iter().enumerate() {
+            for (x, &locked) in row.iter().enumerate() {
+                let endpoint = |kind: &SquareKind| {
+                    matches!(kind, SquareKind::StartSquare | SquareKind::EndSquare)
+                };
+                if !locked || endpoint(&self.squares[y][x]) || endpoint(&squares[y][x]) {
+                    continue;
+                }
+                squares[y][x] = without_search(&self.squares[y][x]);
+                cost[y][x] = self.cost[y][x];
+            }
+        }
+    }
+    // wipes the squares marked by the last search so the same board can be
+    // solved again from scratch
+    pub fn clear_solution(&mut self) {
+        self.reset_search();
+        for square in self.squares.iter_mut().flatten() {
+            match square {
+                SquareKind::PossiblePath
+                | SquareKind::Frontier
+                | SquareKind::BackwardPath
+                | SquareKind::Filled
+                | SquareKind::SolutionPath => *square = SquareKind::Init,
+                _ => {}
+            }
+        }
+    }
+    pub fn reset_search(&mut self) {
+        self.solved = false;
+        self.unsolvable = false;
+        self.last_path_len = 0;
+        self.last_explored = 0;
+        self.expanded = vec![];
+        self.search = None;
+        self.bidirectional = None;
+        self.wall_follower = None;
+        self.iddfs = None;
+        self.simple_path = None;
+        self.turns = None;
+        self.path = vec![];
+        self.dist = vec![vec![None; self.width as usize]; self.height as usize];
+        self.search_start = None;
+    }
+    pub fn resize(&mut self, height: i32, width: i32) {
+        let height = height.clamp(MIN_DIMENSION, MAX_DIMENSION);
+        let width = width.clamp(MIN_DIMENSION, MAX_DIMENSION);
+        if height == self.height && width == self.width {
+            return;
+        }
+
+        self.height = height;
+        self.width = width;
+        self.locked = vec![vec![false; width as usize]; height as usize];
+        self.clear();
+    }
+    /// Resizes the board like `resize`, but refuses a size outside the
+    /// bounds rather than clamping it, leaving the board as it was.
+    pub fn try_resize(&mut self, height: i32, width: i32) -> Result<(), DimensionError> {
+        validate::check_dimensions(height, width)?;
+        self.resize(height, width);
+        Ok(())
+    }
+    /// How many squares the board has, counted without overflowing however
+    /// big it is.
+    pub fn square_count(&self) -> usize {
+        (self.width.max(0) as usize).saturating_mul(self.height.max(0) as usize)
+    }
+    /// How many squares a search could explore, which is every one that
+    /// isn't a wall.
+    pub fn open_squares(&self) -> usize {
+        self.squares
+            .iter()
+            .flatten()
+            .filter(|square_kind| **square_kind != SquareKind::Obstacle)
+            .count()
+    }
+    pub fn find_squares(&self, kind: SquareKind) -> Vec<(i32, i32)> {
+        let mut squares = vec![];
+        for (y, row) in self.squares.iter().enumerate() {
+            for (x, square_kind) in row.iter().enumerate() {
+                if *square_kind == kind {
+                    squares.push((x as i32, y as i32));
+                }
+            }
+        }
+        squares
+    }
+    pub fn find_square(&self, kind: SquareKind) -> Option<(i32, i32)> {
+        for (y, row) in self.squares.iter().enumerate() {
+            for (x, square_kind) in row.iter().enumerate() {
+                if *square_kind == kind {
+                    return Some((x as i32, y as i32));
+                }
+            }
+        }
+        None
+    }
+    pub(crate) fn record_expansion(&mut self, x: i32, y: i32) {
+        self.last_explored += 1;
+        self.expanded.push((x, y));
+    }
+    /// The square at `(x, y)`, or `None` when that's off the board.
+    pub fn get(&self, x: i32, y: i32) -> Option<&SquareKind> {
+        if x < 0 || y < 0 {
+            return None;
+        }
+        self.squares.get(y as usize)?.get(x as usize)
+    }
+    /// Replaces the square at `(x, y)`, returning false and leaving the
+    /// board as it was when that's off the board.
+    pub fn set(&mut self, x: i32, y: i32, kind: SquareKind) -> bool {
+        match self.get_mut(x, y) {
+            Some(square) => {
+                *square = kind;
+                true
+            }
+            None => false,
+        }
+    }
+    fn get_mut(&mut self, x: i32, y: i32) -> Option<&mut SquareKind> {
+        if x < 0 || y < 0 {
+            return None;
+        }
+        self.squares.get_mut(y as usize)?.get_mut(x as usize)
+    }
+    pub fn mark_square(&mut self, x: i32, y: i32, kind: SquareKind) {
+        // the start and end squares, portals and checkpoints keep their own
+        // colors
+        match self.get_mut(x, y) {
+            Some(
+                SquareKind::StartSquare
+                | SquareKind::EndSquare
+                | SquareKind::Portal(_)
+                | SquareKind::Checkpoint(_),
+            )
+            | None => {}
+            Some(square) => *square = kind,
+        }
+    }
+    pub fn set_square(&mut self, x: i32, y: i32, kind: SquareKind, cost: u32) {
+        if self.set(x, y, kind) {
+            self.cost[y as usize][x as usize] = cost;
+        }
+    }
+    pub fn move_endpoint(&mut self, x: i32, y: i32, kind: SquareKind) {
+        // never stack the start and end squares on top of each other
+        match self.get(x, y) {
+            Some(SquareKind::StartSquare | SquareKind::EndSquare) | None => return,
+            Some(_) => {}
+        }
+
+        if let Some((old_x, old_y)) = self.find_square(kind.clone()) {
+            self.set(old_x, old_y, SquareKind::Init);
+        }
+        self.set(x, y, kind);
+    }
+    // places another end square, leaving the ones already on the board
+    pub fn add_end(&mut self, x: i32, y: i32) {
+        match self.get(x, y) {
+            Some(SquareKind::StartSquare | SquareKind::EndSquare) | None => {}
+            Some(_) => {
+                self.set(x, y, SquareKind::EndSquare);
+            }
+        }
+    }
+    /// Places a portal, completing the lowest numbered pair that's still
+    /// missing a side, or does nothing once all `PORTAL_PAIRS` are placed.
+    pub fn add_portal(&mut self, x: i32, y: i32) {
+        match self.get(x, y) {
+            Some(SquareKind::StartSquare | SquareKind::EndSquare | SquareKind::Portal(_))
+            | None => return,
+            Some(_) => {}
+        }
+
+        let unpaired =
+            (0..PORTAL_PAIRS).find(|&id| self.find_squares(SquareKind::Portal(id)).len() < 2);
+        if let Some(id) = unpaired {
+            self.set(x, y, SquareKind::Portal(id));
+        }
+    }
+    // every portal that has another with the same id to jump to
+    fn linked_portals(&self) -> Vec<(i32, i32)> {
+        let mut portals = vec![];
+        for (y, row) in self.squares.iter().enumerate() {
+            for (x, square_kind) in row.iter().enumerate() {
+                let square = (x as i32, y as i32);
+                if let SquareKind::Portal(_) = square_kind {
+                    if !self.portal_partners(square).is_empty() {
+                        portals.push(square);
+                    }
+                }
+            }
+        }
+        portals
+    }
+    pub fn is_obstacle(&self, x: i32, y: i32) -> bool {
+        let (x, y) = self.wrapped(x, y);
+        self.get(x, y) == Some(&SquareKind::Obstacle)
+    }
+    pub fn is_valid_move(&self, x: i32, y: i32) -> bool {
+        let (x, y) = self.wrapped(x, y);
+
+        // check if move is out of bounds
+        let square_kind = match self.get(x, y) {
+            Some(square_kind) => square_kind,
+            None => return false,
+        };
+
+        // check if move is on an obstacle, or the start square,
+        // allow all other kinds.
+
+        match square_kind {
+            SquareKind::Obstacle => false,
+            SquareKind::StartSquare => false,
+            SquareKind::Init => true,
+            SquareKind::SolutionPath => true,
+            SquareKind::PossiblePath => true,
+            SquareKind::Frontier => true,
+            SquareKind::BackwardPath => true,
+            SquareKind::Filled => true,
+            SquareKind::EndSquare => true,
+            SquareKind::Portal(_) => true,
+            SquareKind::Checkpoint(_) => true,
+        }
+    }
+    /// Where a player walking the board by hand ends up stepping `direction`
+    /// from `from`, or None when a wall or the edge of the board is in the
+    /// way. Unlike a search, a player can walk back over the start.
+    pub fn step_player(&self, from: (i32, i32), direction: Direction) -> Option<(i32, i32)> {
+        let (dx, dy) = direction.delta();
+        let (x, y) = self.wrapped(from.0 + dx, from.1 + dy);
+        if self.is_valid_move(x, y) || self.get(x, y) == Some(&SquareKind::StartSquare) {
+            Some((x, y))
+        } else {
+            None
+        }
+    }
+    fn get_possible_moves(&self, parent: (i32, i32)) -> Vec<Move> {
+        let (cur_x, cur_y) = parent;
+        let mut result: Vec<Move> = vec![];
+        let mut all_moves: Vec<Move> = self
+            .neighbour_order
+            .iter()
+            .map(|direction| direction.delta())
+            .filter(|&(dx, dy)| dx == 0 || dy == 0)
+            .map(|(dx, dy)| Move(cur_x + dx, cur_y + dy, parent))
+            .collect();
+
+        if self.allow_diagonals {
+            for (dx, dy) in Direction::DIAGONALS
+                .iter()
+                .map(|direction| direction.delta())
+            {
+                // squeezing between two obstacles that touch at the corners
+                let squeeze =
+                    self.is_obstacle(cur_x + dx, cur_y) && self.is_obstacle(cur_x, cur_y + dy);
+                if self.allow_corner_cutting || !squeeze {
+                    all_moves.push(Move(cur_x + dx, cur_y + dy, parent));
+                }
+            }
+        }
+        all_moves.reverse();
+
+        for m in all_moves {
+            let (x, y) = self.wrapped(m.0, m.1);
+            if self.is_valid_move(x, y) {
+                result.push(Move(x, y, m.2))
+            }
+        }
+        for (x, y) in self.portal_partners(parent) {
+            result.push(Move(x, y, parent));
+        }
+
+        result
+    }
+    /// The portals a search standing on `(x, y)` can jump to, which is none
+    /// unless it's a portal itself.
+    pub fn portal_partners(&self, (x, y): (i32, i32)) -> Vec<(i32, i32)> {
+        match self.get(x, y) {
+            Some(&SquareKind::Portal(id)) => self
+                .find_squares(SquareKind::Portal(id))
+                .into_iter()
+                .filter(|&square| square != (x, y))
+                .collect(),
+            _ => vec![],
+        }
+    }
+    // brings a square off the edge of a wrapping board back onto it, other
+    // boards leave it where it is to be rejected as out of bounds
+    pub(crate) fn wrapped(&self, x: i32, y: i32) -> (i32, i32) {
+        if self.wrap {
+            (x.rem_euclid(self.width), y.rem_euclid(self.height))
+        } else {
+            (x, y)
+        }
+    }
+    fn mark_solution(&mut self, path: &[(i32, i32)]) {
+        self.solved = true;
+        self.last_path_len = path.len();
+        self.path = path.to_vec();
+
+        for square in path {
+            let (x, y) = *square;
+            self.mark_square(x, y, SquareKind::SolutionPath);
+        }
+    }
+    fn heuristic(&self, from: (i32, i32), ends: &[(i32, i32)], portals: &[(i32, i32)]) -> f64 {
+        if self.heuristic == Heuristic::Zero {
+            return 0.0;
+        }
+
+        let heuristic = &self.heuristic;
+        let direct = self.distance_to_nearest(from, ends, heuristic);
+        if portals.is_empty() {
+            return direct;
+        }
+
+        // a route that jumps has to walk to some portal, take at least one
+        // step across and walk from some portal to an end, so it can't be
+        // shorter than that
+        let through_portals = self.distance_to_nearest(from, portals, heuristic)
+            + 1.0
+            + portals
+                .iter()
+                .map(|&portal| self.distance_to_nearest(portal, ends, heuristic))
+                .reduce(f64::min)
+                .unwrap_or(0.0);
+        direct.min(through_portals)
+    }
+    /// The fewest moves from `from` to the nearest of `ends` on an open
+    /// board under the current movement rules, which is the Chebyshev
+    /// distance with diagonals and the Manhattan distance without. Measured
+    /// the same way as A*'s estimate, across a wrapped edge where that's
+    /// nearer.
+    pub fn open_moves(&self, from: (i32, i32), ends: &[(i32, i32)]) -> f64 {
+        let heuristic = if self.allow_diagonals {
+            Heuristic::Chebyshev
+        } else {
+            Heuristic::Manhattan
+        };
+        self.distance_to_nearest(from, ends, &heuristic)
+    }
+    /// The fewest moves from where the last search set off to `(x, y)` on an
+    /// open board, as `open_moves` counts them, or `None` if that search
+    /// didn't reach it.
+    pub fn moves_from_start(&self, (x, y): (i32, i32)) -> Option<f64> {
+        self.dist.get(y as usize)?.get(x as usize)?.as_ref()?;
+        Some(self.open_moves(self.search_start?, &[(x, y)]))
+    }
+    // how far `from` is from the nearest of `ends` as `heuristic` measures it
+    fn distance_to_nearest(
+        &self,
+        from: (i32, i32),
+        ends: &[(i32, i32)],
+        heuristic: &Heuristic,
+    ) -> f64 {
+        let distance = |from, to| match heuristic {
+            Heuristic::Octile if self.allow_diagonals => {
+                octile_distance(from, to, self.diagonal_cost)
+            }
+            Heuristic::Octile | Heuristic::Manhattan => manhattan_distance(from, to) as f64,
+            Heuristic::Euclidean => euclidean_distance(from, to),
+            Heuristic::Chebyshev => chebyshev_distance(from, to) as f64,
+            Heuristic::Zero => 0.0,
+        };
+
+        // on a wrapping board an end on the far side may be closer going the
+        // other way around, across the edge
+        let nearest = |end: i32, from: i32, size: i32| {
+            if !self.wrap || (end - from).abs() * 2 <= size {
+                end
+            } else if end > from {
+                end - size
+            } else {
+                end + size
+            }
+        };
+
+        // aim for the nearest end so the estimate never overshoots
+        ends.iter()
+            .map(|&(x, y)| {
+                let end = (
+                    nearest(x, from.0, self.width),
+                    nearest(y, from.1, self.height),
+                );
+                distance(from, end)
+            })
+            .reduce(f64::min)
+            .unwrap_or(0.0)
+    }
+    // what `algorithm` charges for the move from `parent` to `m`
+    fn step_cost(&self, algorithm: &Algorithm, parent: (i32, i32), m: &Move) -> f64 {
+        let step = match algorithm {
+            Algorithm::Dijkstra => self.cost[m.1 as usize][m.0 as usize] as f64,
+            _ => 1.0,
+        };
+        // both coordinates change on a diagonal, even across a wrapped edge,
+        // but jumping through a portal is never one
+        let diagonal = m.0 != parent.0
+            && m.1 != parent.1
+            && !self.portal_partners(parent).contains(&(m.0, m.1));
+        match algorithm {
+            Algorithm::AStar | Algorithm::Dijkstra if diagonal => step * self.diagonal_cost,
+            _ => step,
+        }
+    }
+    fn push_moves(&mut self, search: &mut Search, parent: (i32, i32), cost: f64) {
+        for m in self.get_possible_moves(parent) {
+            let cost = cost + self.step_cost(&search.algorithm, parent, &m);
+            let (priority, tie) = match search.algorithm {
+                // with nothing to aim by, A* keeps to Dijkstra's order
+                Algorithm::AStar if self.heuristic == Heuristic::Zero => (cost, (0.0, 0.0)),
+                // between squares that look as good as each other, A* goes on
+                // with the one nearer an end, then the one nearer the straight
+                // line from the start to the first end, rather than stepping
+                // around at random
+                Algorithm::AStar => {
+                    let h = self.heuristic((m.0, m.1), &search.ends, &search.portals);
+                    let off_line = off_line_distance((m.0, m.1), search.start, search.ends[0]);
+                    (cost + h, (h, off_line))
+                }
+                Algorithm::Greedy => (
+                    self.heuristic((m.0, m.1), &search.ends, &search.portals),
+                    (0.0, 0.0),
+                ),
+                _ => (cost, (0.0, 0.0)),
+            };
+
+            // squares that were already expanded keep their color
+            if self.get(m.0, m.1) == Some(&SquareKind::Init) {
+                self.mark_square(m.0, m.1, SquareKind::Frontier);
+            }
+            search.frontier.push(Candidate {
+                priority,
+                tie,
+                cost,
+                m,
+            });
+        }
+    }
+    /// Whether a search started by `begin_search` still has squares to expand.
+    pub fn is_searching(&self) -> bool {
+        self.search.is_some()
+            || self.bidirectional.is_some()
+            || self.wall_follower.is_some()
+            || self.iddfs.is_some()
+            || self.simple_path.is_some()
+            || self.turns.is_some()
+    }
+    /// Stops a search started by `begin_search`, leaving the squares it has
+    /// explored so far on the board.
+    pub fn cancel_search(&mut self) {
+        self.search = None;
+        self.bidirectional = None;
+        self.wall_follower = None;
+        self.iddfs = None;
+        self.simple_path = None;
+        self.turns = None;
+    }
+    /// Sets up a search to be advanced with `step`, returning false when
+    /// there's nothing to search because it's already over.
+    pub fn begin_search(&mut self, algorithm: Algorithm) -> bool {
+        self.clear_solution();
+
+        // a board missing either endpoint can't be solved, which `validate`
+        // tells the caller about
+        if self.validate().is_err() {
+            self.unsolvable = true;
+            return false;
+        }
+        let start = self
+            .find_square(SquareKind::StartSquare)
+            .expect("a valid board has a start");
+        let ends = self.find_squares(SquareKind::EndSquare);
+        self.search_start = Some(start);
+
+        if ends.contains(&start) {
+            self.mark_solution(&[start]);
+            return false;
+        }
+
+        match algorithm {
+            Algorithm::Bidirectional => {
+                self.begin_bidirectional(start, &ends);
+                return true;
+            }
+            Algorithm::WallFollower => {
+                self.begin_wall_follower(start);
+                return true;
+            }
+            Algorithm::Iddfs => {
+                self.begin_iddfs(start);
+                return true;
+            }
+            Algorithm::Dfs if self.revisit_policy == RevisitPolicy::CurrentPathOnly => {
+                self.dist[start.1 as usize][start.0 as usize] = Some(0.0);
+                self.begin_simple_path(start);
+                return true;
+            }
+            Algorithm::AStar | Algorithm::Dijkstra if self.turn_cost > 0.0 => {
+                self.dist[start.1 as usize][start.0 as usize] = Some(0.0);
+                self.begin_turn_search(algorithm, start, ends);
+                return true;
+            }
+            _ => {}
+        }
+
+        let mut search = Search {
+            frontier: Frontier::new(&algorithm),
+            algorithm,
+            ends,
+            visited: vec![vec![false; self.width as usize]; self.height as usize],
+            start,
+            came_from: HashMap::new(),
+            portals: self.linked_portals(),
+        };
+        self.dist[start.1 as usize][start.0 as usize] = Some(0.0);
+        self.push_moves(&mut search, start, 0.0);
+        self.search = Some(search);
+
+        true
+    }
+    /// Expands one square of the current search, returning false once the
+    /// search has finished.
+    pub fn step(&mut self) -> bool {
+        if self.bidirectional.is_some() {
+            return self.step_bidirectional();
+        }
+        if self.wall_follower.is_some() {
+            return self.step_wall_follower();
+        }
+        if self.iddfs.is_some() {
+            return self.step_iddfs();
+        }
+        if self.simple_path.is_some() {
+            return self.step_simple_path();
+        }
+        if self.turns.is_some() {
+            return self.step_turn_search();
+        }
+
+        let mut search = match self.search.take() {
+            Some(search) => search,
+            None => return false,
+        };
+
+        // squares that were already expanded are skipped without using up a step
+        while let Some(Candidate { cost, m, .. }) = search.frontier.pop() {
+            let (cur_x, cur_y) = (m.0, m.1);
+
+            if self.get(cur_x, cur_y) == Some(&SquareKind::EndSquare) {
+                self.dist[cur_y as usize][cur_x as usize] = Some(cost);
+                search.came_from.insert((cur_x, cur_y), m.2);
+                let path = reconstruct_path(&search.came_from, search.start, (cur_x, cur_y));
+                self.mark_solution(&path);
+                return false;
+            }
+
+            if search.visited[cur_y as usize][cur_x as usize] {
+                continue;
+            }
+
+            search.visited[cur_y as usize][cur_x as usize] = true;
+            search.came_from.insert((cur_x, cur_y), m.2);
+            self.record_expansion(cur_x, cur_y);
+            self.dist[cur_y as usize][cur_x as usize] = Some(cost);
+
+            self.mark_square(cur_x, cur_y, SquareKind::PossiblePath);
+            self.push_moves(&mut search, (cur_x, cur_y), cost);
+
+            self.search = Some(search);
+            return true;
+        }
+
+        // the frontier ran dry without reaching the end
+        self.unsolvable = true;
+        false
+    }
+    /// Runs a whole search, returning the path from the start to the end it
+    /// reached, or an empty path when there's none.
+    pub fn run(&mut self, algorithm: Algorithm) -> Vec<(i32, i32)> {
+        if self.begin_search(algorithm) {
+            while self.step() {}
+        }
+
+        self.path.to_vec()
+    }
+    pub fn traverse(&mut self) -> Vec<(i32, i32)> {
+        self.run(Algorithm::Dfs)
+    }
+    pub fn traverse_bfs(&mut self) -> Vec<(i32, i32)> {
+        self.run(Algorithm::Bfs)
+    }
+    pub fn traverse_astar(&mut self) -> Vec<(i32, i32)> {
+        self.run(Algorithm::AStar)
+    }
+    pub fn traverse_dijkstra(&mut self) -> Vec<(i32, i32)> {
+        self.run(Algorithm::Dijkstra)
+    }
+    // heads for whichever square looks closest to an end, ignoring how far
+    // it has come. Usually expands fewer squares than A* but the path it
+    // finds isn't always the shortest.
+    pub fn traverse_greedy(&mut self) -> Vec<(i32, i32)> {
+        self.run(Algorithm::Greedy)
+    }
+    /// Marks every square that can be reached from the start, whether or not
+    /// an end is among them, returning how many there are. Anything left
+    /// unmarked is cut off from the start.
+    pub fn flood_fill_from_start(&mut self) -> usize {
+        self.clear_solution();
+
+        for (x, y) in self.reachable_from_start() {
+            self.record_expansion(x, y);
+            self.mark_square(x, y, SquareKind::PossiblePath);
+        }
+        self.last_explored
+    }
+    /// Every square a route from the start can get to under the current
+    /// movement rules, nearest first, leaving the board as it is. After a
+    /// failed solve this is the region the start is walled into.
+    pub fn reachable_from_start(&self) -> Vec<(i32, i32)> {
+        let start = match self.find_square(SquareKind::StartSquare) {
+            Some(start) => start,
+            None => return vec![],
+        };
+
+        let mut reached = vec![vec![false; self.width as usize]; self.height as usize];
+        let mut result = vec![];
+        let mut queue = VecDeque::from(vec![start]);
+        while let Some(square) = queue.pop_front() {
+            for m in self.get_possible_moves(square) {
+                if reached[m.1 as usize][m.0 as usize] {
+                    continue;
+                }
+                reached[m.1 as usize][m.0 as usize] = true;
+                result.push((m.0, m.1));
+                queue.push_back((m.0, m.1));
+            }
+        }
+        result
+    }
+}
+
+// the square as it is on the board itself, without whatever the last search
+// marked it as
+pub(crate) fn without_search(kind: &SquareKind) -> SquareKind {
+    match kind {
+        SquareKind::PossiblePath
+        | SquareKind::Frontier
+        | SquareKind::BackwardPath
+        | SquareKind::Filled
+        | SquareKind::SolutionPath => SquareKind::Init,
+        other => other.clone(),
+    }
+}
+
+fn init_squares(height: i32, width: i32) -> Vec<Vec<SquareKind>> {
+    let mut squares: Vec<Vec<SquareKind>> = Vec::with_capacity(height as usize);
+
+    // keep the default endpoints on the board when it's smaller than usual
+    let (start_x, start_y) = (
+        DEFAULT_START_X.min(width - 1),
+        DEFAULT_START_Y.min(height - 1),
+    );
+    let (end_x, end_y) = (DEFAULT_END_X.min(width - 1), DEFAULT_END_Y.min(height - 1));
+
+    for column in 0..height {
+        squares.push(Vec::with_capacity(width as usize));
+        for row in 0..width {
+            let mut square_type = SquareKind::Init;
+            if column == start_y && row == start_x {
+                square_type = SquareKind::StartSquare;
+            } else if column == end_y && row == end_x {
+                square_type = SquareKind::EndSquare;
+            }
+            squares[column as usize].push(square_type)
+        }
+    }
+
+    squares
+}
+
+fn init_cost(height: i32, width: i32) -> Vec<Vec<u32>> {
+    vec![vec![DEFAULT_COST; width as usize]; height as usize]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+
+    #[test]
+    fn reconstruct_path_keeps_both_ends() {
+        let mut came_from = HashMap::new();
+        came_from.insert((1, 0), (0, 0));
+        came_from.insert((2, 0), (1, 0));
+
+        let path = reconstruct_path(&came_from, (0, 0), (2, 0));
+        assert_eq!(path, vec![(0, 0), (1, 0), (2, 0)]);
+    }
+
+    #[test]
+    fn init_squares_has_a_row_per_unit_of_height() {
+        let squares = init_squares(4, 7);
+        assert_eq!(squares.len(), 4);
+        assert!(squares.iter().all(|row| row.len() == 7));
+
+        let maze = Maze::new(4, 7);
+        assert_eq!(maze.get(0, 3), Some(&SquareKind::Init));
+        assert_eq!(maze.get(0, 4), None);
+    }
+
+    #[test]
+    fn traverse_starts_from_a_moved_start() {
+        let mut maze = Maze::default();
+        maze.move_endpoint(6, 2, SquareKind::StartSquare);
+
+        let path = maze.traverse();
+        assert_eq!(path.first(), Some(&(6, 2)));
+        assert_eq!(manhattan_distance(path[0], path[1]), 1);
+        assert_ne!(maze.get(1, 5), Some(&SquareKind::StartSquare));
+    }
+
+    #[test]
+    fn clear_forgets_the_last_solve() {
+        let mut maze = Maze::default();
+        maze.traverse();
+        assert!(maze.solved);
+
+        maze.clear();
+        assert!(!maze.solved);
+        assert!(!maze.traverse().is_empty());
+    }
+
+    #[test]
+    fn solving_keeps_the_endpoints() {
+        let mut maze = Maze::default();
+        let path = maze.traverse();
+        assert!(path.len() > 2);
+
+        assert_eq!(maze.get(1, 5), Some(&SquareKind::StartSquare));
+        assert_eq!(maze.get(8, 5), Some(&SquareKind::EndSquare));
+    }
+
+    #[test]
+    fn large_open_board_expands_each_square_once() {
+        let mut maze = Maze::new(50, 50);
+        maze.move_endpoint(49, 49, SquareKind::EndSquare);
+
+        let path = maze.traverse();
+        assert_eq!(path.last(), Some(&(49, 49)));
+
+        let mut expanded = maze.expanded.clone();
+        expanded.sort_unstable();
+        expanded.dedup();
+        assert_eq!(expanded.len(), maze.expanded.len());
+        assert!(maze.last_explored < maze.open_squares());
+    }
+
+    #[test]
+    fn bfs_finds_a_shorter_route_than_dfs() {
+        let board = "#########\n#S      #\n# ##### #\n#E      #\n#########\n";
+        let mut dfs = Maze::from_ascii(board).unwrap();
+        let mut bfs = dfs.clone();
+        let dfs_path = dfs.traverse();
+        let bfs_path = bfs.traverse_bfs();
+        assert!(bfs_path.len() < dfs_path.len());
+    }
+
+    #[test]
+    fn bfs_crosses_an_open_board_in_the_manhattan_distance() {
+        let mut maze = Maze::new(9, 10);
+        maze.move_endpoint(0, 0, SquareKind::StartSquare);
+        maze.move_endpoint(9, 8, SquareKind::EndSquare);
+        let path = maze.traverse_bfs();
+        // the path lists the start as well as every square moved onto
+        assert_eq!(path.len() - 1, manhattan_distance((0, 0), (9, 8)) as usize);
+        assert_eq!(path.len(), 18);
+    }
+
+    #[test]
+    fn astar_explores_less_than_bfs_past_a_diagonal_wall() {
+        let wall: Vec<(i32, i32)> = (3..=26).map(|x| (x, 29 - x)).collect();
+        let mut bfs = Maze::from_spec(30, 30, (2, 2), (27, 27), &wall).unwrap();
+        let mut astar = bfs.clone();
+
+        let bfs_path = bfs.traverse_bfs();
+        let astar_path = astar.traverse_astar();
+        assert_eq!(astar_path.len(), bfs_path.len());
+        assert!(astar.last_explored < bfs.last_explored);
+    }
+
+    #[test]
+    fn dijkstra_takes_a_cheap_detour() {
+        let mut maze = Maze::default();
+        let expensive: Vec<(i32, i32)> = (2..=7).map(|x| (x, 5)).collect();
+        for &(x, y) in &expensive {
+            maze.set_square(x, y, SquareKind::Init, 9);
+        }
+
+        let path = maze.traverse_dijkstra();
+        assert_eq!(path.last(), Some(&(8, 5)));
+        assert!(path.iter().all(|square| !expensive.contains(square)));
+    }
+
+    #[test]
+    fn moving_the_endpoints_resets_their_old_squares() {
+        let mut maze = Maze::default();
+        maze.move_endpoint(3, 2, SquareKind::StartSquare);
+        maze.move_endpoint(6, 7, SquareKind::EndSquare);
+
+        assert_eq!(maze.get(3, 2), Some(&SquareKind::StartSquare));
+        assert_eq!(maze.get(6, 7), Some(&SquareKind::EndSquare));
+        assert_eq!(maze.get(1, 5), Some(&SquareKind::Init));
+        assert_eq!(maze.get(8, 5), Some(&SquareKind::Init));
+
+        // neither can be dropped on the other
+        maze.move_endpoint(6, 7, SquareKind::StartSquare);
+        assert_eq!(maze.find_square(SquareKind::StartSquare), Some((3, 2)));
+    }
+
+    #[test]
+    fn diagonals_squeeze_past_corners_only_when_allowed() {
+        // the start is walled in except for the gap between (2, 5) and (1, 4)
+        let walls = [(2, 5), (1, 4), (0, 5), (1, 6), (0, 4), (0, 6), (2, 6)];
+        let mut maze = Maze::from_spec(10, 9, (1, 5), (8, 5), &walls).unwrap();
+        maze.allow_diagonals = true;
+        assert!(maze.traverse_bfs().is_empty());
+
+        maze.allow_corner_cutting = true;
+        let path = maze.traverse_bfs();
+        assert_eq!(path[1], (2, 4));
+        assert!(maze.is_valid_path(&path));
+    }
+
+    #[test]
+    fn walled_in_end_is_unsolvable() {
+        let walls = [(7, 5), (9, 5), (8, 4), (8, 6)];
+        let mut maze = Maze::from_spec(10, 9, (1, 5), (8, 5), &walls).unwrap();
+
+        assert!(maze.traverse().is_empty());
+        assert!(maze.unsolvable);
+        assert!(!maze.solved);
+    }
+
+    #[test]
+    fn astar_reports_fewer_explored_squares_than_dfs() {
+        let mut astar = Maze::new(30, 30);
+        astar.move_endpoint(25, 20, SquareKind::EndSquare);
+        let mut dfs = astar.clone();
+
+        astar.traverse_astar();
+        dfs.traverse();
+        assert_eq!(astar.last_path_len, 40);
+        assert!(astar.last_explored < dfs.last_explored);
+    }
+
+    #[test]
+    fn bfs_stops_at_the_nearer_end() {
+        let mut maze = Maze::default();
+        maze.add_end(3, 5);
+        assert_eq!(maze.find_squares(SquareKind::EndSquare).len(), 2);
+
+        let path = maze.traverse_bfs();
+        assert_eq!(path, vec![(1, 5), (2, 5), (3, 5)]);
+    }
+
+    #[test]
+    fn reconstruct_path_follows_a_hand_built_map() {
+        let mut came_from = HashMap::new();
+        came_from.insert((1, 0), (0, 0));
+        came_from.insert((1, 1), (1, 0));
+        came_from.insert((2, 1), (1, 1));
+        // a branch the path doesn't take
+        came_from.insert((0, 1), (0, 0));
+
+        assert_eq!(
+            reconstruct_path(&came_from, (0, 0), (2, 1)),
+            vec![(0, 0), (1, 0), (1, 1), (2, 1)]
+        );
+        assert!(reconstruct_path(&came_from, (0, 0), (5, 5)).is_empty());
+
+        // a loop that never gets back to the start
+        came_from.insert((0, 0), (2, 1));
+        assert!(reconstruct_path(&came_from, (9, 9), (2, 1)).is_empty());
+    }
+
+    #[test]
+    fn solutions_run_from_start_to_end() {
+        for algorithm in [Algorithm::Dfs, Algorithm::Bfs, Algorithm::AStar].iter() {
+            let mut maze = Maze::default();
+            let path = maze.run(algorithm.clone());
+            assert_eq!(path.first(), Some(&(1, 5)), "{:?}", algorithm);
+            assert_eq!(path.last(), Some(&(8, 5)), "{:?}", algorithm);
+        }
+    }
+
+    #[test]
+    fn clear_walls_keeps_only_the_endpoints() {
+        let mut maze = Maze::from_spec(10, 9, (2, 2), (7, 6), &[(3, 3), (4, 4), (5, 5)]).unwrap();
+        maze.clear_walls();
+
+        let mut drawn = maze
+            .squares
+            .iter()
+            .flatten()
+            .filter(|&kind| *kind != SquareKind::Init);
+        assert_eq!(drawn.next(), Some(&SquareKind::StartSquare));
+        assert_eq!(drawn.next(), Some(&SquareKind::EndSquare));
+        assert_eq!(drawn.next(), None);
+        assert_eq!(maze.find_square(SquareKind::StartSquare), Some((2, 2)));
+        assert_eq!(maze.find_square(SquareKind::EndSquare), Some((7, 6)));
+    }
+
+    #[test]
+    fn clear_solution_keeps_the_walls() {
+        let mut maze = Maze::from_spec(10, 9, (1, 5), (8, 5), &[(4, 4), (4, 5), (4, 6)]).unwrap();
+        maze.traverse_bfs();
+        assert!(!maze.find_squares(SquareKind::SolutionPath).is_empty());
+
+        maze.clear_solution();
+        assert!(maze.find_squares(SquareKind::SolutionPath).is_empty());
+        assert!(maze.find_squares(SquareKind::PossiblePath).is_empty());
+        assert_eq!(maze.find_squares(SquareKind::Obstacle).len(), 3);
+        assert!(!maze.solved);
+    }
+
+    #[test]
+    fn queued_squares_are_frontier_until_expanded() {
+        let mut maze = Maze::default();
+        assert!(maze.begin_search(Algorithm::Bfs));
+        let mut queued = maze.find_squares(SquareKind::Frontier);
+        queued.sort_unstable();
+        assert_eq!(queued, vec![(0, 5), (1, 4), (1, 6), (2, 5)]);
+
+        assert!(maze.step());
+        let expanded = maze.expanded[0];
+        assert_eq!(
+            maze.get(expanded.0, expanded.1),
+            Some(&SquareKind::PossiblePath)
+        );
+        for square in queued.into_iter().filter(|&square| square != expanded) {
+            assert_eq!(maze.get(square.0, square.1), Some(&SquareKind::Frontier));
+        }
+    }
+
+    #[test]
+    fn bfs_distances_are_manhattan_on_an_open_board() {
+        let mut maze = Maze::default();
+        maze.traverse_bfs();
+
+        let mut measured = 0;
+        for (y, row) in maze.dist.iter().enumerate() {
+            for (x, dist) in row.iter().enumerate() {
+                if let Some(dist) = dist {
+                    let expected = manhattan_distance((x as i32, y as i32), (1, 5));
+                    assert_eq!(*dist, expected as f64, "({}, {})", x, y);
+                    measured += 1;
+                }
+            }
+        }
+        assert!(measured >= maze.last_explored);
+    }
+
+    #[test]
+    fn wrapping_board_solves_across_the_edge() {
+        let mut maze = Maze::from_ascii("S#  E\n##   \n").unwrap();
+        assert!(maze.traverse_bfs().is_empty());
+
+        maze.wrap = true;
+        assert_eq!(maze.traverse_bfs(), vec![(0, 0), (4, 0)]);
+    }
+
+    // greedy heads for whichever square looks closest to the end, so it
+    // expands fewer squares than A* here but walks the long way round
+    #[test]
+    fn greedy_explores_less_than_astar_but_takes_a_longer_path() {
+        let text = "S   #  \n  #   #\n  #    \n     # \n  # ##E\n";
+        let mut astar = Maze::from_ascii(text).unwrap();
+        astar.traverse_astar();
+        let mut greedy = Maze::from_ascii(text).unwrap();
+        greedy.traverse_greedy();
+
+        assert!(greedy.solved);
+        assert_eq!((greedy.last_explored, astar.last_explored), (14, 17));
+        assert_eq!((greedy.last_path_len, astar.last_path_len), (13, 11));
+    }
+
+    #[test]
+    fn flood_fill_marks_only_the_starts_region() {
+        let mut maze = Maze::from_ascii("S  #  \n   # E\n   #  \n").unwrap();
+        assert_eq!(maze.flood_fill_from_start(), 8);
+        assert_eq!(maze.find_squares(SquareKind::PossiblePath).len(), 8);
+        for (x, _) in maze.find_squares(SquareKind::PossiblePath) {
+            assert!(x < 3);
+        }
+        // the wall cuts the end's side off, so it's left as it was
+        assert_eq!(maze.find_squares(SquareKind::Init).len(), 5);
+        assert!(!maze.solved);
+    }
+
+    // a board as proptest draws it: its size, which squares are walls and
+    // where the endpoints go. Shrinking clears walls and makes the board
+    // smaller, so a failure comes down to a small board with few walls.
+    #[derive(Clone, Debug)]
+    struct Board {
+        width: i32,
+        height: i32,
+        walls: Vec<bool>,
+        start: (i32, i32),
+        end: (i32, i32),
+    }
+
+    impl Board {
+        fn maze(&self) -> Maze {
+            let walls: Vec<(i32, i32)> = (0..self.height)
+                .flat_map(|y| (0..self.width).map(move |x| (x, y)))
+                .zip(&self.walls)
+                .filter(|&(square, &wall)| wall && square != self.start && square != self.end)
+                .map(|(square, _)| square)
+                .collect();
+            Maze::from_spec(self.width, self.height, self.start, self.end, &walls).unwrap()
+        }
+    }
+
+    fn boards() -> impl Strategy<Value = Board> {
+        (MIN_DIMENSION..=12, MIN_DIMENSION..=12)
+            .prop_flat_map(|(width, height)| {
+                let walls =
+                    prop::collection::vec(prop::bool::weighted(0.3), (width * height) as usize);
+                (walls, (0..width, 0..height), (0..width, 0..height)).prop_map(
+                    move |(walls, start, end)| Board {
+                        width,
+                        height,
+                        walls,
+                        start,
+                        end,
+                    },
+                )
+            })
+            .prop_filter("the endpoints are different squares", |board| {
+                board.start != board.end
+            })
+    }
+
+    proptest! {
+        #[test]
+        fn bfs_finds_the_shortest_path_on_random_boards(board in boards(), diagonals in any::<bool>()) {
+            let mut maze = board.maze();
+            maze.allow_diagonals = diagonals;
+
+            let bfs = maze.traverse_bfs();
+            let solvable = maze.solved;
+            let dfs = maze.traverse();
+            prop_assert_eq!(maze.solved, solvable);
+            let astar = maze.traverse_astar();
+            prop_assert_eq!(maze.solved, solvable);
+            if solvable {
+                for path in &[&bfs, &dfs, &astar] {
+                    prop_assert!(maze.is_valid_path(path));
+                }
+                prop_assert!(bfs.len() <= dfs.len());
+                // a diagonal costs more than a straight move to A*, so it can
+                // trade more squares for a cheaper path
+                if !diagonals {
+                    prop_assert_eq!(astar.len(), bfs.len());
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn diagonal_moves_beat_an_orthogonal_detour() {
+        for algorithm in [Algorithm::AStar, Algorithm::Dijkstra].iter() {
+            let mut maze = Maze::from_ascii("S    \n     \n     \n     \n    E\n").unwrap();
+            maze.allow_diagonals = true;
+            let path = maze.run(algorithm.clone());
+
+            assert_eq!(path, vec![(0, 0), (1, 1), (2, 2), (3, 3), (4, 4)]);
+            let cost = maze.dist[4][4].unwrap();
+            assert!((cost - 4.0 * std::f64::consts::SQRT_2).abs() < 1e-9);
+        }
+    }
+
+    #[test]
+    fn squares_off_the_board_are_none() {
+        let mut maze = Maze::new(4, 3);
+        assert_eq!(maze.get(0, 0), Some(&SquareKind::Init));
+        assert_eq!(maze.get(-1, 0), None);
+        assert_eq!(maze.get(0, -1), None);
+        assert_eq!(maze.get(3, 0), None);
+        assert_eq!(maze.get(0, 4), None);
+        assert_eq!(maze.get(i32::MAX, i32::MIN), None);
+
+        // and writing to them does nothing rather than panicking
+        assert!(!maze.set(0, -1, SquareKind::Obstacle));
+        assert!(!maze.set(3, 4, SquareKind::Obstacle));
+        assert!(maze.find_squares(SquareKind::Obstacle).is_empty());
+    }
+
+    #[test]
+    fn the_shortest_path_goes_through_a_portal() {
+        // the long way round takes fourteen moves, the portals three
+        let board = "##########\n#S0     ##\n####### ##\n#E0     ##\n##########\n";
+        for algorithm in [
+            Algorithm::Bfs,
+            Algorithm::AStar,
+            Algorithm::Dijkstra,
+            Algorithm::Bidirectional,
+        ]
+        .iter()
+        {
+            let mut maze = Maze::from_ascii(board).unwrap();
+            let path = maze.run(algorithm.clone());
+            assert_eq!(
+                path,
+                vec![(1, 1), (2, 1), (2, 3), (1, 3)],
+                "{:?}",
+                algorithm
+            );
+            assert!(maze.is_valid_path(&path));
+        }
+
+        // with the portals as the only way across, every solver takes them
+        let board = "#########\n#S#   #E#\n# #   # #\n#0#   #0#\n#########\n";
+        for algorithm in [Algorithm::Dfs, Algorithm::Greedy, Algorithm::Iddfs].iter() {
+            let mut maze = Maze::from_ascii(board).unwrap();
+            let path = maze.run(algorithm.clone());
+            assert!(maze.solved, "{:?}", algorithm);
+            assert!(path.contains(&(1, 3)) && path.contains(&(7, 3)));
+        }
+    }
+
+    #[test]
+    fn locked_squares_survive_fills_and_clears() {
+        let mut maze = Maze::new(15, 15);
+        maze.set_square(3, 3, SquareKind::Obstacle, DEFAULT_COST);
+        maze.set_locked(3, 3, true);
+        // a locked open square stays open too
+        maze.set_locked(4, 3, true);
+
+        maze.random_fill(1.0, Some(1));
+        assert_eq!(maze.get(3, 3), Some(&SquareKind::Obstacle));
+        assert_eq!(maze.get(4, 3), Some(&SquareKind::Init));
+        assert_eq!(maze.get(5, 3), Some(&SquareKind::Obstacle));
+
+        maze.clear_walls();
+        assert_eq!(maze.get(3, 3), Some(&SquareKind::Obstacle));
+        assert_eq!(maze.get(5, 3), Some(&SquareKind::Init));
+
+        maze.set_locked(3, 3, false);
+        maze.clear_walls();
+        assert_eq!(maze.get(3, 3), Some(&SquareKind::Init));
+    }
+
+    #[test]
+    fn astar_without_a_heuristic_explores_like_dijkstra() {
+        for seed in 0..5 {
+            let mut astar = Maze::new(31, 31);
+            astar.random_fill(0.25, Some(seed));
+            astar.heuristic = Heuristic::Zero;
+            let mut dijkstra = astar.clone();
+
+            let path = astar.run(Algorithm::AStar);
+            assert_eq!(path.len(), dijkstra.run(Algorithm::Dijkstra).len());
+            assert_eq!(astar.last_explored, dijkstra.last_explored, "seed {}", seed);
+        }
+    }
+
+    #[test]
+    fn the_neighbour_order_steers_depth_first_search() {
+        let text = "     \n  S  \n     \n    E\n";
+        let mut default_order = Maze::from_ascii(text).unwrap();
+        let mut down_first = Maze::from_ascii(text).unwrap();
+        down_first.neighbour_order = [
+            Direction::Down,
+            Direction::Up,
+            Direction::Left,
+            Direction::Right,
+        ];
+
+        let path = default_order.traverse();
+        let other = down_first.traverse();
+        assert_ne!(path, other);
+        assert!(default_order.check_path(&path).is_ok());
+        assert!(down_first.check_path(&other).is_ok());
+        assert_eq!(path[1], (3, 1));
+        assert_eq!(other[1], (2, 2));
+    }
+
+    #[test]
+    fn a_star_keeps_close_to_the_straight_line() {
+        let text = "S         \n          \n          \n          \n          \n         E\n";
+        let mut maze = Maze::from_ascii(text).unwrap();
+        let path = maze.traverse_astar();
+        assert_eq!(path.len(), 15);
+        for &square in &path {
+            assert!(
+                off_line_distance(square, (0, 0), (9, 5)) < 1.0,
+                "{:?} strays from the line",
+                square
+            );
+        }
+    }
+
+    #[test]
+    fn an_unsolvable_board_reaches_only_the_start_side() {
+        let mut maze = Maze::from_ascii("S #  \n  # E\n  #  \n").unwrap();
+        assert!(maze.run(Algorithm::Bfs).is_empty());
+        assert!(maze.unsolvable);
+
+        let mut reachable = maze.reachable_from_start();
+        reachable.sort_unstable();
+        assert_eq!(reachable, vec![(0, 1), (0, 2), (1, 0), (1, 1), (1, 2)]);
+        assert!(reachable.iter().all(|&(x, _)| x < 2));
+    }
+}
@@ -1,116 +1,342 @@
-use druid::piet::Color;
-use druid::widget::{Button, Flex, Painter};
-use druid::Data;
-use druid::RenderContext;
-use druid::{AppLauncher, PlatformError, Widget, WidgetExt, WindowDesc};
-use std::collections::HashMap;
-use std::rc::Rc;
+use druid::kurbo::Circle;
+use druid::lens;
+use druid::piet::{Color, FontFamily, Text, TextLayout, TextLayoutBuilder};
+use druid::widget::{
+    Button, Checkbox, Controller, Flex, Label, LineBreaking, Painter, ProgressBar, Scroll,
+    SizedBox, Slider, Stepper, TextBox, ViewSwitcher,
+};
+use druid::{AppLauncher, ExtEventSink, LensExt, PlatformError, Widget, WidgetExt, WindowDesc};
+use druid::{
+    Data, Env, Event, EventCtx, HotKey, KbKey, KeyEvent, Lens, LifeCycle, LifeCycleCtx, PaintCtx,
+    SysMods, TimerToken,
+};
+use druid::{Point, Rect, RenderContext, Size};
+use pollen::ascii::checkpoint_glyph;
+use pollen::path::{describe_directions, path_to_directions};
+use pollen::{
+    diff_grids, Algorithm, Direction, Heuristic, Maze, MazeGenerator, PathCount, RevisitPolicy,
+    SolveReport, SquareKind,
+};
+use pollen::{
+    DEFAULT_COST, DEFAULT_HEIGHT, DEFAULT_NEIGHBOUR_ORDER, DEFAULT_WIDTH, MAX_DIMENSION,
+    MIN_DIMENSION,
+};
+use std::collections::HashSet;
+use std::env;
+use std::fs;
+use std::path::Path;
+use std::process;
 use std::sync::Arc;
+use std::time::{Duration, Instant};
 
-mod traverse;
+mod board_snapshot;
+mod cli;
+mod history;
+mod inspector;
+mod letterbox;
+mod palette;
+mod render;
+mod settings;
+mod viewport;
+mod window_fit;
+mod worker;
 
-const DEFAULT_HEIGHT: i32 = 9;
-const DEFAULT_WIDTH: i32 = 10;
+use board_snapshot::{BoardSnapshot, Snapshots, SNAPSHOT_SLOTS};
+use history::History;
+use inspector::Inspector;
+use letterbox::Letterbox;
+use palette::{GridStyle, Palette};
+use render::Render;
+use settings::Settings;
+use viewport::Viewport;
+use window_fit::{FitWindow, FIT_WINDOW};
+use worker::{
+    Carving, Generated, Run, GENERATE_DONE, GENERATE_PROGRESS, SOLVE_DONE, SOLVE_PROGRESS,
+};
 
-const DEFAULT_START_X: i32 = 1;
-const DEFAULT_START_Y: i32 = 5;
+// where the save, load, import and export buttons keep the current maze
+const MAZE_FILE: &str = "maze.json";
+const ASCII_FILE: &str = "maze.txt";
+const IMAGE_FILE: &str = "maze.png";
+// where the import picture button reads a drawn maze from, and how light a
+// square of it has to be to come out open
+const PICTURE_FILE: &str = "picture.png";
+const PICTURE_THRESHOLD: u8 = 128;
+const REPORT_FILE: &str = "report.json";
+const IMAGE_CELL_PX: u32 = 24;
 
-const DEFAULT_END_X: i32 = 8;
-const DEFAULT_END_Y: i32 = 5;
+// cost of crossing a square painted as mud
+const WEIGHTED_COST: u32 = 5;
 
-#[derive(Clone)]
-enum ButtonState {
-    NewGame,
-    Obstacle,
-    Start,
-}
+// delay between frames of an animated search, in milliseconds
+const DEFAULT_SPEED_MS: f64 = 100.0;
+const MIN_SPEED_MS: f64 = 10.0;
+const MAX_SPEED_MS: f64 = 1000.0;
+// delay between squares of the path being drawn in once an animated search
+// finds it, kept short whatever the search's own speed
+const PATH_DRAW_MS: f64 = 20.0;
 
-#[derive(Clone, PartialEq, Debug)]
-pub enum SquareKind {
-    Init,
-    Obstacle,
-    PossiblePath,
-    SolutionPath,
-    StartSquare,
-    EndSquare,
-}
+// share of squares the random fill turns into walls, and how many boards it
+// tries before giving up on finding one with a path
+const DEFAULT_DENSITY: f64 = 0.3;
+const MAX_DENSITY: f64 = 0.6;
+const FILL_ATTEMPTS: u32 = 100;
 
-#[derive(Clone, Debug)]
-pub struct Node {
-    x: i32,
-    y: i32,
+// how many expansions an animated search makes between repaints
+const MAX_STEPS_PER_FRAME: u32 = 100;
 
-    parent: Option<Rc<Node>>,
-}
+// how thick the lines between squares can be drawn, in pixels
+const DEFAULT_GRID_WIDTH: f64 = 1.0;
+const MIN_GRID_WIDTH: f64 = 0.5;
+const MAX_GRID_WIDTH: f64 = 4.0;
 
-impl Node {
-    pub fn new(x: i32, y: i32) -> Node {
-        Node {
-            x: x,
-            y: y,
-            parent: None,
-        }
-    }
+// the range the diagonal cost slider covers, from a diagonal costing no more
+// than a straight step to costing as much as the two it stands in for
+const MIN_DIAGONAL_COST: f64 = 1.0;
+const MAX_DIAGONAL_COST: f64 = 2.0;
 
-    pub fn add(&self, child_node: &mut Node) {
-        child_node.parent = Some(Rc::new(self.clone()));
-    }
+// the most A* and Dijkstra can be asked to add for each change of direction
+const MAX_TURN_COST: f64 = 5.0;
 
-    pub fn find_reverse_path(&self) -> Vec<(i32, i32)> {
-        let mut result: Vec<(i32, i32)> = vec![];
-        let mut current_node = &Rc::new(self.clone());
+// boards with at least this many squares are solved on a worker thread, so
+// the window keeps responding while a slow search runs
+const BACKGROUND_SQUARES: usize = 2500;
 
-        while !current_node.parent.is_none() {
-            result.push((current_node.x, current_node.y));
-            current_node = current_node.parent.as_ref().unwrap();
-        }
+// the solvers run side by side by the compare button
+const COMPARED: [(&str, Algorithm); 3] = [
+    ("DFS", Algorithm::Dfs),
+    ("BFS", Algorithm::Bfs),
+    ("A*", Algorithm::AStar),
+];
+const COMPARISON_HEIGHT: f64 = 200.0;
 
-        result
-    }
+// how the height left over from the controls is split between the board and
+// the space under the status lines
+const BOARD_FLEX: f64 = 9.0;
+const SPACER_FLEX: f64 = 2.0;
+
+// how many routes the count paths button looks for before settling for
+// "at least"
+const PATH_COUNT_CAP: usize = 1000;
+
+// how thick the outline around the square just expanded is drawn
+const CURRENT_OUTLINE: f64 = 3.0;
+
+// how strongly locked squares are shaded over
+const LOCKED_ALPHA: f64 = 0.15;
+
+// how strongly the squares the start can still reach are tinted after a
+// solve finds no path
+const REACHABLE_ALPHA: f64 = 0.35;
+
+// how strongly the squares a player has walked over are tinted, and how big
+// the player is drawn relative to its square
+const TRAIL_ALPHA: f64 = 0.4;
+const PLAYER_RADIUS: f64 = 0.3;
+
+// how strongly a wall is hinted at on the open square under the mouse
+// before it's placed
+const PREVIEW_ALPHA: f64 = 0.3;
+
+// how strongly the squares two seeds disagree on are tinted
+const DIFF_ALPHA: f64 = 0.5;
+
+// the turn by turn directions scroll once they're taller than this
+const DIRECTIONS_HEIGHT: f64 = 60.0;
+
+#[derive(Clone)]
+enum ButtonState {
+    NewGame,
+    Obstacle,
+    Weighted,
+    Erase,
+    MoveStart,
+    MoveEnd,
+    AddEnd,
+    Portal,
+    Checkpoint,
+    Lock,
+    Start,
 }
 
-#[derive(Clone, Debug)]
-// (x, y, previous position)
-struct Move(i32, i32, Node);
+// whether the animator is advancing a search on its own
+#[derive(Clone, PartialEq)]
+enum AnimState {
+    Idle,
+    Running,
+    Paused,
+    // drawing in the path an animated search just found, with how many of
+    // its squares are drawn so far
+    PathDraw(usize),
+}
 
 trait Metadata {
     fn new() -> Self;
     fn gen_board(&self, height: i32, width: i32) -> Flex<State>;
-    fn init_state(height: i32, width: i32) -> Arc<Vec<Vec<SquareKind>>>;
+    fn maze_mut(&mut self) -> &mut Maze;
+    fn edit(&mut self, edit: impl FnOnce(&mut Maze));
+    fn undo(&mut self);
+    fn redo(&mut self);
+    fn reset_history(&mut self);
+    fn snapshot(&self) -> BoardSnapshot;
+    fn restore(&mut self, snapshot: &BoardSnapshot);
+    fn press(&mut self, x: i32, y: i32, erase: bool);
+    fn paint(&mut self, x: i32, y: i32, erase: bool);
+    fn lock(&mut self, x: i32, y: i32, locked: bool);
     fn clear(&mut self);
-    // traversal methods
-    fn is_valid_move(&self, x: i32, y: i32) -> bool;
-    fn get_possible_moves(&self, parent: Node) -> Vec<Move>;
-    fn traverse(&mut self) -> Vec<(i32, i32)>;
+    fn load_default(&mut self);
+    fn import(&mut self, imported: Maze);
+    fn resize(&mut self, height: i32, width: i32);
+    fn start(&mut self, sink: ExtEventSink);
+    fn pause(&mut self);
+    fn resume(&mut self);
+    fn step_once(&mut self);
+    fn cancel(&mut self);
+    fn advance(&mut self);
+    fn begin_path_draw(&mut self);
+    fn draw_path(&mut self, drawn: usize);
+    fn finish_path_draw(&mut self);
+    fn run_steps(&mut self, limit: u32) -> bool;
+    fn remember_solve(&mut self);
+    fn replay(&mut self);
+    fn replay_steps(&mut self, limit: u32);
+    fn solve(&mut self) -> Vec<(i32, i32)>;
+    fn solve_checkpoints(&mut self);
+    fn play(&mut self);
+    fn move_player(&mut self, direction: Direction);
+    fn compare(&mut self);
+    fn compare_seeds(&mut self, seeds: [Option<u64>; 2]);
+    fn generate(&mut self, sink: ExtEventSink, generator: MazeGenerator, seed: Option<u64>);
+    fn carved(&mut self, carving: &Carving);
+    fn generated(&mut self, generated: &Generated);
+    fn step_interval(&self) -> Duration;
+    fn solve_report(&self) -> SolveReport;
 }
 
-#[derive(Clone, Data)]
+#[derive(Clone, Data, Lens)]
 struct State {
     button_state: Arc<ButtonState>,
-    width: i32,
-    height: i32,
-    solved: bool,
-    state: Arc<Vec<Vec<SquareKind>>>,
+    algorithm: Arc<Algorithm>,
+    generator: Arc<MazeGenerator>,
+    // typed in to generate the same maze again, left empty for a random one
+    seed: String,
+    // the seed `seed` is compared against
+    other_seed: String,
+    density: f64,
+    // keep filling until the board has a path, not just once
+    reroll: bool,
+    // which leg failed when the last solve through the checkpoints found no
+    // path
+    checkpoint_error: Option<String>,
+    // how many routes from the start to an end the board had when they were
+    // last counted
+    path_count: Option<Arc<PathCount>>,
+    // how many boards the last solvable fill went through to find one
+    fill_attempts: Option<u32>,
+    animate: bool,
+    anim: Arc<AnimState>,
+    palette: Arc<Palette>,
+    grid: GridStyle,
+    // write how far the last search got to reach each square inside it
+    show_distances: bool,
+    // describe the square under the mouse next to the cursor
+    inspect: bool,
+    // draw explored squares as open ones, for a clean look at the solution
+    hide_explored: bool,
+    // tint what the start can reach once a solve has failed, which is found
+    // as the solve finishes, so the missing gap in the walls stands out
+    show_reachable: bool,
+    reachable: Option<Arc<HashSet<(i32, i32)>>>,
+    // where the player walking the board with the arrow keys stands, while
+    // there is one, and every square it has stood on in order
+    player: Option<(i32, i32)>,
+    trail: Arc<Vec<(i32, i32)>>,
+    speed_ms: f64,
+    steps_per_frame: u32,
+    // how many calls to `step` the last solve made and the time spent in
+    // them, leaving out the waits between frames of an animated one
+    solve_steps: usize,
+    solve_time: Duration,
+    // the algorithm the last solve ran, which the selector may since have
+    // moved off
+    solved_with: Arc<Algorithm>,
+    // the solve running on a worker thread and how many squares it has
+    // expanded so far
+    solving: Option<Run>,
+    explored: usize,
+    // the maze being carved on a worker thread
+    generating: Option<Run>,
+    // how far the running search has got, estimated as the share of the
+    // board's open squares it has explored, which are counted as it starts,
+    // or how much of the maze being generated is carved
+    progress: f64,
+    open_squares: usize,
+    // the squares the last finished search expanded in order, and the board
+    // it finished on, so it can be played back without searching again
+    record: Arc<Vec<(i32, i32)>>,
+    recorded: Option<Arc<Maze>>,
+    // how far through `record` a replay has got
+    replay: Option<usize>,
+    // the square the animated search or replay expanded last, outlined
+    // until the next step
+    current: Option<(i32, i32)>,
+    maze: Arc<Maze>,
+    // a copy of the board solved by each of `COMPARED`, shown under it until
+    // closed
+    comparison: Option<Arc<Vec<Maze>>>,
+    // copies of the board carved from `seed` and `other_seed`, shown under it
+    // until closed
+    seed_diff: Option<Arc<SeedDiff>>,
+    // edits to the current board, forgotten whenever the board is replaced
+    history: Arc<History>,
+    // boards put aside to come back to after trying out some edits
+    snapshots: Arc<Snapshots>,
 }
 
 impl Metadata for State {
-    fn init_state(height: i32, width: i32) -> Arc<Vec<Vec<SquareKind>>> {
-        let mut state: Vec<Vec<SquareKind>> = vec![vec![]];
-
-        for column in 0..height {
-            state.push(vec![]);
-            for row in 0..width {
-                let mut square_type = SquareKind::Init;
-                if column == DEFAULT_START_Y && row == DEFAULT_START_X {
-                    square_type = SquareKind::StartSquare;
-                } else if column == DEFAULT_END_Y && row == DEFAULT_END_X {
-                    square_type = SquareKind::EndSquare;
-                }
-                state[column as usize].push(square_type)
-            }
+    fn new() -> Self {
+        State {
+            button_state: Arc::new(ButtonState::NewGame),
+            algorithm: Arc::new(Algorithm::Dfs),
+            generator: Arc::new(MazeGenerator::Backtracker),
+            seed: String::new(),
+            other_seed: String::new(),
+            density: DEFAULT_DENSITY,
+            reroll: false,
+            checkpoint_error: None,
+            path_count: None,
+            fill_attempts: None,
+            animate: false,
+            anim: Arc::new(AnimState::Idle),
+            palette: Arc::new(Palette::light()),
+            grid: GridStyle::default(),
+            show_distances: false,
+            inspect: false,
+            hide_explored: false,
+            show_reachable: false,
+            reachable: None,
+            player: None,
+            trail: Arc::new(vec![]),
+            speed_ms: DEFAULT_SPEED_MS,
+            steps_per_frame: 1,
+            solve_steps: 0,
+            solve_time: Duration::ZERO,
+            solved_with: Arc::new(Algorithm::Dfs),
+            solving: None,
+            generating: None,
+            explored: 0,
+            progress: 0.0,
+            open_squares: 0,
+            record: Arc::new(vec![]),
+            recorded: None,
+            replay: None,
+            current: None,
+            maze: Arc::new(Maze::new(DEFAULT_HEIGHT, DEFAULT_WIDTH)),
+            comparison: None,
+            seed_diff: None,
+            history: Arc::new(History::default()),
+            snapshots: Arc::new(Snapshots::default()),
         }
-
-        Arc::new(state)
     }
     fn gen_board(&self, height: i32, width: i32) -> Flex<State> {
         let mut board = Flex::column();
@@ -119,100 +345,575 @@ impl Metadata for State {
         }
         board
     }
+    fn maze_mut(&mut self) -> &mut Maze {
+        Arc::make_mut(&mut self.maze)
+    }
+    fn edit(&mut self, edit: impl FnOnce(&mut Maze)) {
+        let maze = Arc::make_mut(&mut self.maze);
+        Arc::make_mut(&mut self.history).record(maze, edit);
+    }
+    fn undo(&mut self) {
+        if self.history.can_undo() {
+            let maze = Arc::make_mut(&mut self.maze);
+            Arc::make_mut(&mut self.history).undo(maze);
+        }
+    }
+    fn redo(&mut self) {
+        if self.history.can_redo() {
+            let maze = Arc::make_mut(&mut self.maze);
+            Arc::make_mut(&mut self.history).redo(maze);
+        }
+    }
+    fn reset_history(&mut self) {
+        self.history = Arc::new(History::default());
+    }
+    fn snapshot(&self) -> BoardSnapshot {
+        BoardSnapshot::take(&self.maze)
+    }
+    // puts `snapshot` back on the board. Whatever is searching the board is
+    // stopped first, and a background solve's result won't land on the
+    // restored board, since restoring copies it.
+    fn restore(&mut self, snapshot: &BoardSnapshot) {
+        self.cancel();
+        self.reset_history();
+        self.comparison = None;
+        self.seed_diff = None;
+        self.checkpoint_error = None;
+        self.path_count = None;
+        self.player = None;
+        self.trail = Arc::new(vec![]);
+        self.reachable = None;
+        snapshot.restore(self.maze_mut());
+    }
+    fn press(&mut self, x: i32, y: i32, erase: bool) {
+        match *self.button_state {
+            ButtonState::Obstacle | ButtonState::Weighted | ButtonState::Erase => {
+                self.paint(x, y, erase)
+            }
+            ButtonState::MoveStart => {
+                self.edit(|maze| maze.move_endpoint(x, y, SquareKind::StartSquare))
+            }
+            ButtonState::MoveEnd => {
+                self.edit(|maze| maze.move_endpoint(x, y, SquareKind::EndSquare))
+            }
+            ButtonState::AddEnd => self.edit(|maze| maze.add_end(x, y)),
+            ButtonState::Portal => self.edit(|maze| maze.add_portal(x, y)),
+            ButtonState::Checkpoint => self.edit(|maze| maze.add_checkpoint(x, y)),
+            ButtonState::Lock => self.lock(x, y, !erase),
+            ButtonState::NewGame | ButtonState::Start => {}
+        }
+    }
+    fn paint(&mut self, x: i32, y: i32, erase: bool) {
+        let (kind, cost) = match *self.button_state {
+            ButtonState::Obstacle | ButtonState::Weighted if erase => {
+                (SquareKind::Init, DEFAULT_COST)
+            }
+            ButtonState::Erase => (SquareKind::Init, DEFAULT_COST),
+            ButtonState::Obstacle => (SquareKind::Obstacle, DEFAULT_COST),
+            ButtonState::Weighted => (SquareKind::Init, WEIGHTED_COST),
+            ButtonState::Lock => return self.lock(x, y, !erase),
+            _ => return,
+        };
+
+        // brush strokes pass over the endpoints, and over squares that are
+        // already painted without copying the board
+        match self.maze.get(x, y) {
+            Some(SquareKind::StartSquare | SquareKind::EndSquare) | None => return,
+            Some(square_kind)
+                if *square_kind == kind && self.maze.cost[y as usize][x as usize] == cost =>
+            {
+                return
+            }
+            Some(_) => {}
+        }
+
+        self.edit(|maze| maze.set_square(x, y, kind, cost));
+    }
+    // locks aren't part of the undo history, they only say what the
+    // generators, fills and clears should leave alone
+    fn lock(&mut self, x: i32, y: i32, locked: bool) {
+        if self.maze.is_locked(x, y) != locked {
+            self.maze_mut().set_locked(x, y, locked);
+        }
+    }
     fn clear(&mut self) {
         self.button_state = Arc::new(ButtonState::NewGame);
-        self.state = State::init_state(self.height, self.width);
+        self.algorithm = Arc::new(Algorithm::Dfs);
+        self.reset_history();
+        self.comparison = None;
+        self.seed_diff = None;
+        self.checkpoint_error = None;
+        self.path_count = None;
+        self.player = None;
+        self.trail = Arc::new(vec![]);
+        self.reachable = None;
+        self.solve_steps = 0;
+        self.solve_time = Duration::ZERO;
+        self.record = Arc::new(vec![]);
+        self.recorded = None;
+        self.replay = None;
+        self.current = None;
+        self.maze_mut().clear();
     }
-    fn new() -> Self {
-        State {
-            button_state: Arc::new(ButtonState::NewGame),
-            height: DEFAULT_HEIGHT,
-            width: DEFAULT_WIDTH,
-            solved: false,
-            state: Self::init_state(DEFAULT_HEIGHT, DEFAULT_WIDTH),
+    // back to the board the app opens with, whatever size and movement rules
+    // the current one has
+    fn load_default(&mut self) {
+        self.cancel();
+        self.clear();
+        self.maze = Arc::new(Maze::new(DEFAULT_HEIGHT, DEFAULT_WIDTH));
+    }
+    // swaps in a board read from a file, keeping the movement rules picked
+    // for the old one
+    fn import(&mut self, mut imported: Maze) {
+        imported.allow_diagonals = self.maze.allow_diagonals;
+        imported.allow_corner_cutting = self.maze.allow_corner_cutting;
+        imported.wrap = self.maze.wrap;
+        imported.diagonal_cost = self.maze.diagonal_cost;
+        imported.turn_cost = self.maze.turn_cost;
+        imported.revisit_policy = self.maze.revisit_policy.clone();
+        imported.heuristic = self.maze.heuristic;
+        imported.neighbour_order = self.maze.neighbour_order;
+        self.maze = Arc::new(imported);
+        self.reset_history();
+    }
+    fn resize(&mut self, height: i32, width: i32) {
+        // the steppers report their value on every event, so only copy the
+        // maze when the size actually changes
+        if height != self.maze.height || width != self.maze.width {
+            self.reset_history();
+            self.maze_mut().resize(height, width);
+        }
+    }
+    fn start(&mut self, sink: ExtEventSink) {
+        // the status line says what's wrong with the board
+        if self.maze.validate().is_err() {
+            return;
+        }
+
+        self.replay = None;
+        self.checkpoint_error = None;
+        self.reachable = None;
+        self.button_state = Arc::new(ButtonState::Start);
+        self.progress = 0.0;
+        self.open_squares = self.maze.open_squares();
+        if self.animate {
+            let algorithm = (*self.algorithm).clone();
+            self.solve_steps = 0;
+            self.solve_time = Duration::ZERO;
+            self.solved_with = self.algorithm.clone();
+            if self.maze_mut().begin_search(algorithm) {
+                self.anim = Arc::new(AnimState::Running);
+            }
+        } else if self.maze.square_count() >= BACKGROUND_SQUARES {
+            if let Some(run) = self.solving.take() {
+                worker::cancel(&run);
+            }
+            let algorithm = (*self.algorithm).clone();
+            self.explored = 0;
+            self.solved_with = self.algorithm.clone();
+            self.solving = Some(worker::spawn(sink, self.maze.clone(), algorithm));
+        } else {
+            self.solve();
+        }
+    }
+    fn pause(&mut self) {
+        if *self.anim == AnimState::Running {
+            self.anim = Arc::new(AnimState::Paused);
+        }
+    }
+    fn resume(&mut self) {
+        if *self.anim == AnimState::Paused {
+            self.anim = Arc::new(AnimState::Running);
         }
     }
-    fn is_valid_move(&self, x: i32, y: i32) -> bool {
-        // check if move is out of bounds
-        if y < 0 || y >= self.height || x < 0 || x >= self.width {
-            return false;
+    // advances the search by exactly one expansion and leaves it paused,
+    // setting one up first if nothing is running yet
+    fn step_once(&mut self) {
+        if self.replay.is_some() {
+            self.anim = Arc::new(AnimState::Paused);
+            self.replay_steps(1);
+            return;
         }
+        if !self.maze.is_searching() {
+            if self.maze.validate().is_err() {
+                return;
+            }
 
-        // check if move is on an obstacle, or the start square,
-        // allow all other kinds.
-        let square_kind = &self.state[y as usize][x as usize];
+            self.checkpoint_error = None;
+            self.reachable = None;
+            self.button_state = Arc::new(ButtonState::Start);
+            self.progress = 0.0;
+            self.open_squares = self.maze.open_squares();
+            let algorithm = (*self.algorithm).clone();
+            self.solve_steps = 0;
+            self.solve_time = Duration::ZERO;
+            self.solved_with = self.algorithm.clone();
+            if !self.maze_mut().begin_search(algorithm) {
+                return;
+            }
+        }
 
-        return match square_kind {
-            SquareKind::Obstacle => false,
-            SquareKind::StartSquare => false,
-            SquareKind::Init => true,
-            SquareKind::SolutionPath => true,
-            SquareKind::PossiblePath => true,
-            SquareKind::EndSquare => true,
-        };
+        self.anim = Arc::new(AnimState::Paused);
+        self.run_steps(1);
     }
-    fn get_possible_moves(&self, parent: Node) -> Vec<Move> {
-        let cur_x = parent.x;
-        let cur_y = parent.y;
-        let mut result: Vec<Move> = vec![];
-        let mut all_moves = vec![
-            Move(cur_x + 1, cur_y, parent.clone()),
-            Move(cur_x - 1, cur_y, parent.clone()),
-            Move(cur_x, cur_y - 1, parent.clone()),
-            Move(cur_x, cur_y + 1, parent.clone()),
-        ];
-        all_moves.reverse();
+    // stops the search where it is, leaving what it explored on the board
+    fn cancel(&mut self) {
+        // the worker sends back what it explored before stopping
+        if let Some(run) = &self.solving {
+            worker::cancel(run);
+        }
+        // and a maze still being carved is dropped
+        if let Some(run) = self.generating.take() {
+            worker::cancel(&run);
+        }
+        if self.maze.is_searching() {
+            self.maze_mut().cancel_search();
+        }
+        self.finish_path_draw();
+        self.replay = None;
+        self.current = None;
+        self.anim = Arc::new(AnimState::Idle);
+    }
+    // one frame of an animated search, stopping early if it finishes
+    fn advance(&mut self) {
+        if let AnimState::PathDraw(drawn) = *self.anim {
+            self.draw_path(drawn);
+            return;
+        }
+
+        let steps = self.steps_per_frame.clamp(1, MAX_STEPS_PER_FRAME);
+        if self.replay.is_some() {
+            self.replay_steps(steps);
+        } else if !self.run_steps(steps) && self.maze.solved {
+            self.begin_path_draw();
+        }
+    }
+    // takes the path the search just marked back off the board, to be drawn
+    // in again a square at a time from the start
+    fn begin_path_draw(&mut self) {
+        let path = self.maze.path.clone();
+        let maze = self.maze_mut();
+        for &(x, y) in &path {
+            maze.mark_square(x, y, SquareKind::PossiblePath);
+        }
+        self.anim = Arc::new(AnimState::PathDraw(0));
+    }
+    // draws the next square of the path, going idle as soon as the last one
+    // is drawn or the board stops having a path to draw
+    fn draw_path(&mut self, drawn: usize) {
+        let next = match self.maze.path.get(drawn) {
+            Some(&square) if self.maze.solved => square,
+            _ => {
+                self.anim = Arc::new(AnimState::Idle);
+                return;
+            }
+        };
 
-        for m in all_moves {
-            if self.is_valid_move(m.0, m.1) {
-                result.push(m)
+        self.maze_mut()
+            .mark_square(next.0, next.1, SquareKind::SolutionPath);
+        self.anim = Arc::new(if drawn + 1 < self.maze.path.len() {
+            AnimState::PathDraw(drawn + 1)
+        } else {
+            AnimState::Idle
+        });
+    }
+    // puts the rest of a path being drawn in on the board all at once
+    fn finish_path_draw(&mut self) {
+        let drawn = match *self.anim {
+            AnimState::PathDraw(drawn) => drawn,
+            _ => return,
+        };
+        let rest: Vec<(i32, i32)> = self.maze.path.iter().skip(drawn).copied().collect();
+        let maze = self.maze_mut();
+        for (x, y) in rest {
+            maze.mark_square(x, y, SquareKind::SolutionPath);
+        }
+    }
+    // steps the search up to `limit` times, adding the steps and the time
+    // they took to the solve's totals. Returns whether it's still going.
+    fn run_steps(&mut self, limit: u32) -> bool {
+        let started = Instant::now();
+        let maze = Arc::make_mut(&mut self.maze);
+        let mut searching = true;
+        for _ in 0..limit {
+            self.solve_steps += 1;
+            searching = maze.step();
+            if !searching {
+                break;
             }
         }
 
-        result
+        self.solve_time += started.elapsed();
+        self.progress = explored_share(self.maze.last_explored, self.open_squares);
+        self.current = if searching {
+            self.maze.expanded.last().copied()
+        } else {
+            None
+        };
+        if !searching {
+            self.remember_solve();
+        }
+        searching
+    }
+    // keeps a finished search to be replayed, along with where the start
+    // could get to if it found no path
+    fn remember_solve(&mut self) {
+        self.record = Arc::new(self.maze.expanded.clone());
+        self.recorded = Some(self.maze.clone());
+        self.reachable = if self.maze.unsolvable {
+            let reachable = self.maze.reachable_from_start();
+            Some(Arc::new(reachable.into_iter().collect()))
+        } else {
+            None
+        };
     }
-    fn traverse(&mut self) -> Vec<(i32, i32)> {
-        let mut visited = HashMap::new();
+    // puts the board the last search finished on back without any of its
+    // search, then animates its expansions back onto it in the order they
+    // happened, ending on the finished board
+    fn replay(&mut self) {
+        let finished = match &self.recorded {
+            Some(finished) => finished.clone(),
+            None => return,
+        };
 
-        let mut stack: Vec<Move> =
-            self.get_possible_moves(Node::new(DEFAULT_START_X, DEFAULT_START_Y));
+        self.cancel();
+        let mut board = (*finished).clone();
+        board.clear_solution();
+        board.search_start = finished.search_start;
+        self.maze = Arc::new(board);
+        self.replay = Some(0);
+        self.anim = Arc::new(AnimState::Running);
+    }
+    fn replay_steps(&mut self, limit: u32) {
+        let (position, finished) = match (self.replay, &self.recorded) {
+            (Some(position), Some(finished)) => (position, finished.clone()),
+            _ => return,
+        };
 
-        while stack.len() > 0 {
-            let m = stack.pop().unwrap();
-            let (cur_x, cur_y) = (m.0, m.1);
-            let parent = &m.2.clone();
-            let mut child = Node::new(cur_x, cur_y);
-            parent.add(&mut child);
+        let end = (position + limit as usize).min(self.record.len());
+        let record = self.record.clone();
+        let maze = self.maze_mut();
+        for &(x, y) in &record[position..end] {
+            // squares on the path are shown as explored until the end
+            let kind = match finished.get(x, y) {
+                Some(SquareKind::SolutionPath) => SquareKind::PossiblePath,
+                Some(kind) => kind.clone(),
+                None => continue,
+            };
+            maze.mark_square(x, y, kind);
+            maze.dist[y as usize][x as usize] = finished.dist[y as usize][x as usize];
+            maze.last_explored += 1;
+        }
 
-            if self.state[cur_y as usize][cur_x as usize] == SquareKind::EndSquare {
-                self.solved = true;
+        if end == record.len() {
+            self.maze = finished;
+            self.replay = None;
+            self.current = None;
+        } else {
+            self.replay = Some(end);
+            self.current = end.checked_sub(1).map(|last| record[last]);
+        }
+    }
+    fn solve(&mut self) -> Vec<(i32, i32)> {
+        let algorithm = (*self.algorithm).clone();
+        self.solve_steps = 0;
+        self.solve_time = Duration::ZERO;
+        self.solved_with = self.algorithm.clone();
 
-                for square in parent.find_reverse_path() {
-                    let (x, y) = square;
-                    Arc::make_mut(&mut self.state)[y as usize][x as usize] =
-                        SquareKind::SolutionPath;
-                }
+        let started = Instant::now();
+        if self.maze_mut().begin_search(algorithm) {
+            self.run_steps(u32::MAX);
+        }
+        self.solve_time = started.elapsed();
+        self.maze.path.to_vec()
+    }
+    // solves the board leg by leg through each checkpoint, all at once, with
+    // nothing recorded to replay
+    fn solve_checkpoints(&mut self) {
+        if self.maze.validate().is_err() {
+            return;
+        }
 
-                return parent.find_reverse_path();
-            }
+        let algorithm = (*self.algorithm).clone();
+        self.replay = None;
+        self.record = Arc::new(vec![]);
+        self.recorded = None;
+        self.reachable = None;
+        self.button_state = Arc::new(ButtonState::Start);
+        self.solve_steps = 0;
+        self.solved_with = self.algorithm.clone();
 
-            let value = format!("{},{}", cur_x, cur_y);
+        let started = Instant::now();
+        let result = self.maze_mut().solve_checkpoints(algorithm);
+        self.solve_time = started.elapsed();
+        self.checkpoint_error = result.err().map(|err| err.to_string());
+    }
+    // puts a player on the start to be walked to an end by hand, or takes it
+    // off the board again
+    fn play(&mut self) {
+        self.player = match self.player {
+            Some(_) => None,
+            None => self.maze.find_square(SquareKind::StartSquare),
+        };
+        self.trail = Arc::new(self.player.into_iter().collect());
+    }
+    fn move_player(&mut self, direction: Direction) {
+        let player = match self.player {
+            Some(player) => player,
+            None => return,
+        };
+        // the game is over once an end is reached
+        if self.maze.get(player.0, player.1) == Some(&SquareKind::EndSquare) {
+            return;
+        }
 
-            if *visited.get(&value).unwrap_or(&false) {
-                continue;
-            }
+        if let Some(next) = self.maze.step_player(player, direction) {
+            self.player = Some(next);
+            Arc::make_mut(&mut self.trail).push(next);
+        }
+    }
+    // solves a copy of the board with each compared solver, so the board
+    // itself is left as it was
+    fn compare(&mut self) {
+        if self.maze.validate().is_err() {
+            return;
+        }
 
-            visited.insert(value, true);
+        let solved = COMPARED
+            .iter()
+            .map(|(_, algorithm)| {
+                let mut maze = (*self.maze).clone();
+                maze.run(algorithm.clone());
+                maze
+            })
+            .collect();
+        self.comparison = Some(Arc::new(solved));
+    }
+    // carves a copy of the board from each seed with the selected generator,
+    // leaving the board itself as it was
+    fn compare_seeds(&mut self, seeds: [Option<u64>; 2]) {
+        let carve = |seed| {
+            let mut maze = (*self.maze).clone();
+            maze.generate(&self.generator, seed);
+            maze
+        };
+        let boards = [carve(seeds[0]), carve(seeds[1])];
+        let differ = diff_grids(&boards[0], &boards[1]);
+        self.seed_diff = Some(Arc::new(SeedDiff { boards, differ }));
+    }
+    // carves a maze over the board with `generator`, on a worker thread for
+    // a board big enough to take a while
+    fn generate(&mut self, sink: ExtEventSink, generator: MazeGenerator, seed: Option<u64>) {
+        if let Some(run) = self.generating.take() {
+            worker::cancel(&run);
+        }
+        if self.maze.square_count() >= BACKGROUND_SQUARES {
+            self.progress = 0.0;
+            let source = self.maze.clone();
+            self.generating = Some(worker::spawn_generation(sink, source, generator, seed));
+        } else {
+            self.reset_history();
+            self.maze_mut().generate(&generator, seed);
+        }
+    }
+    fn carved(&mut self, carving: &Carving) {
+        if is_current(&self.generating, &carving.run) {
+            self.progress = carving.carved;
+        }
+    }
+    // the carved maze replaces the board unless the board was edited while
+    // it was being carved
+    fn generated(&mut self, generated: &Generated) {
+        if !is_current(&self.generating, &generated.run) {
+            return;
+        }
+        self.generating = None;
+        self.progress = 1.0;
+        if Arc::ptr_eq(&generated.source, &self.maze) {
+            self.reset_history();
+            self.maze = generated.maze.clone();
+        }
+    }
+    // the last solve, credited to the algorithm that ran it
+    fn solve_report(&self) -> SolveReport {
+        SolveReport::new(&self.maze, (*self.solved_with).clone(), self.solve_time)
+    }
+    fn step_interval(&self) -> Duration {
+        if let AnimState::PathDraw(_) = *self.anim {
+            return Duration::from_millis(PATH_DRAW_MS as u64);
+        }
+        let speed_ms = self.speed_ms.clamp(MIN_SPEED_MS, MAX_SPEED_MS);
+        Duration::from_millis(speed_ms as u64)
+    }
+}
 
-            Arc::make_mut(&mut self.state)[cur_y as usize][cur_x as usize] =
-                SquareKind::PossiblePath;
+// the fill color for a square, shared by the painter and the PNG renderer.
+// With `hide_explored` the squares a search reached are drawn as if it never
+// had, leaving just the solution standing out.
+fn square_color(palette: &Palette, maze: &Maze, x: i32, y: i32, hide_explored: bool) -> Color {
+    // a painter can outlive a resize for a frame, so squares that have just
+    // left the board are drawn blank
+    let kind = match maze.get(x, y) {
+        Some(SquareKind::PossiblePath | SquareKind::Frontier | SquareKind::BackwardPath)
+            if hide_explored =>
+        {
+            &SquareKind::Init
+        }
+        Some(kind) => kind,
+        None => return palette.init,
+    };
+    let cost = maze.cost[y as usize][x as usize];
 
-            for m in self.get_possible_moves(child) {
-                stack.push(m)
-            }
+    match kind {
+        SquareKind::Init | SquareKind::PossiblePath | SquareKind::BackwardPath
+            if cost > DEFAULT_COST =>
+        {
+            palette.weighted(cost)
         }
+        kind => palette.color(kind),
+    }
+}
+
+// whether the square at `x`, `y` is hinted as where a click would put a
+// wall, which it is only while the mouse is over it
+fn previews_wall(data: &State, x: i32, y: i32, hovered: bool) -> bool {
+    let placing = matches!(*data.button_state, ButtonState::Obstacle);
+    placing && hovered && data.maze.get(x, y) == Some(&SquareKind::Init)
+}
+
+// the text written over the square at `x`, `y`, if any
+fn square_label(data: &State, x: i32, y: i32) -> Option<String> {
+    // portals are numbered so the pairs can be told apart
+    match data.maze.get(x, y) {
+        Some(SquareKind::Portal(id)) => Some(id.to_string()),
+        Some(SquareKind::Checkpoint(id)) => checkpoint_glyph(*id).map(String::from),
+        // move counts rather than what the search paid, which weights
+        // diagonals
+        Some(_) if data.show_distances => data
+            .maze
+            .moves_from_start((x, y))
+            .map(|moves| moves.to_string()),
+        _ => None,
+    }
+}
 
-        vec![]
+// whether `run` is the worker `running` says is still wanted
+fn is_current(running: &Option<Run>, run: &Run) -> bool {
+    matches!(running, Some(current) if Arc::ptr_eq(current, run))
+}
+
+// the share of `open` squares that `explored` makes up, which stays short of
+// the whole board for a search that finds an end early
+fn explored_share(explored: usize, open: usize) -> f64 {
+    (explored as f64 / open.max(1) as f64).min(1.0)
+}
+
+// whole step counts as they are, costs that include diagonals to one place
+fn format_distance(dist: f64) -> String {
+    if dist.fract() == 0.0 {
+        dist.to_string()
+    } else {
+        format!("{:.1}", dist)
     }
 }
 
@@ -220,24 +921,185 @@ fn square(y: i32, x: i32) -> impl Widget<State> {
     Painter::new(move |ctx, data: &State, _| {
         let bounds = ctx.size().to_rect();
 
-        let color = match data.state[y as usize][x as usize] {
-            SquareKind::Init => &Color::WHITE,
-            SquareKind::Obstacle => &Color::BLACK,
-            SquareKind::PossiblePath => &Color::WHITE,
-            SquareKind::SolutionPath => &Color::YELLOW,
-            SquareKind::StartSquare => &Color::GREEN,
-            SquareKind::EndSquare => &Color::PURPLE,
-        };
-        ctx.fill(bounds, color);
-        ctx.stroke(bounds.inset(-0.5), &Color::BLACK, 1.0);
+        let color = square_color(&data.palette, &data.maze, x, y, data.hide_explored);
+        ctx.fill(bounds, &color);
+        if data.maze.is_locked(x, y) {
+            ctx.fill(bounds, &data.palette.text.with_alpha(LOCKED_ALPHA));
+        }
+        if let Some(reachable) = &data.reachable {
+            if data.show_reachable && data.maze.unsolvable && reachable.contains(&(x, y)) {
+                ctx.fill(bounds, &data.palette.start.with_alpha(REACHABLE_ALPHA));
+            }
+        }
+        if data.player.is_some() && data.trail.contains(&(x, y)) {
+            ctx.fill(bounds, &data.palette.solution_path.with_alpha(TRAIL_ALPHA));
+        }
+        if data.player == Some((x, y)) {
+            let radius = bounds.width().min(bounds.height()) * PLAYER_RADIUS;
+            ctx.fill(Circle::new(bounds.center(), radius), &data.palette.current);
+        }
+        if previews_wall(data, x, y, ctx.is_hot()) {
+            ctx.fill(bounds, &data.palette.obstacle.with_alpha(PREVIEW_ALPHA));
+        }
+        if let Some(width) = data.grid.stroke_width() {
+            ctx.stroke(bounds.inset(-width / 2.0), &data.palette.grid, width);
+        }
+        // a finished or cancelled search has no current square, whatever was
+        // left behind
+        let animating = data.maze.is_searching() || data.replay.is_some();
+        if animating && data.current == Some((x, y)) {
+            // kept inside the square so the neighbours' borders don't cover it
+            let outline = bounds.inset(-CURRENT_OUTLINE / 2.0);
+            ctx.stroke(outline, &data.palette.current, CURRENT_OUTLINE);
+        }
+
+        if let Some(text) = square_label(data, x, y) {
+            // shrink the text so longer numbers still fit inside the square
+            let size = (bounds.width() / text.len().max(2) as f64).min(bounds.height() * 0.6);
+            let layout = ctx
+                .text()
+                .new_text_layout(text)
+                .font(FontFamily::SYSTEM_UI, size)
+                .text_color(data.palette.text)
+                .build()
+                .unwrap();
+            let origin = bounds.center() - layout.size().to_vec2() / 2.0;
+            ctx.draw_text(&layout, origin);
+        }
     })
-    .on_click(move |_ctx, data: &mut State, _env| {
-        Arc::make_mut(&mut data.state)[y as usize][x as usize] = match *data.button_state {
-            ButtonState::Obstacle => SquareKind::Obstacle,
-            ButtonState::NewGame => SquareKind::Init,
-            ButtonState::Start => SquareKind::Init,
+    .controller(Brush { x, y })
+}
+
+// fills `bounds` with the whole board, one rectangle per square and no grid
+// lines, for boards drawn too small to show them
+fn paint_maze(ctx: &mut PaintCtx, data: &State, maze: &Maze, bounds: Rect) {
+    let cell = Size::new(
+        bounds.width() / maze.width as f64,
+        bounds.height() / maze.height as f64,
+    );
+    for y in 0..maze.height {
+        for x in 0..maze.width {
+            let origin = Point::new(
+                bounds.x0 + x as f64 * cell.width,
+                bounds.y0 + y as f64 * cell.height,
+            );
+            let color = square_color(&data.palette, maze, x, y, data.hide_explored);
+            ctx.fill(Rect::from_origin_size(origin, cell), &color);
+        }
+    }
+}
+
+// one of the compared boards with what its solver found. It can't be edited,
+// so the whole board is drawn by a single painter.
+fn comparison_board(index: usize) -> impl Widget<State> {
+    let board = Painter::new(move |ctx, data: &State, _| {
+        if let Some(maze) = data
+            .comparison
+            .as_ref()
+            .and_then(|boards| boards.get(index))
+        {
+            let bounds = ctx.size().to_rect();
+            paint_maze(ctx, data, maze, bounds);
+        }
+    });
+
+    let label = Label::new(move |data: &State, _env: &Env| {
+        let name = COMPARED[index].0;
+        match data
+            .comparison
+            .as_ref()
+            .and_then(|boards| boards.get(index))
+        {
+            Some(maze) if maze.solved => format!(
+                "{}: path length {}  explored {}",
+                name, maze.last_path_len, maze.last_explored
+            ),
+            Some(maze) => format!("{}: no path, explored {}", name, maze.last_explored),
+            None => String::new(),
+        }
+    });
+
+    Flex::column()
+        .with_flex_child(board, 1.0)
+        .with_child(label)
+        .padding(5.0)
+}
+
+// the two boards compared by the compare seeds button and where they differ
+struct SeedDiff {
+    boards: [Maze; 2],
+    differ: Vec<(i32, i32)>,
+}
+
+// one of the boards carved by `seed_diff`, with the squares the other seed
+// carved differently tinted
+fn seed_diff_board(index: usize) -> impl Widget<State> {
+    Painter::new(move |ctx, data: &State, _| {
+        let diff = match &data.seed_diff {
+            Some(diff) => diff,
+            None => return,
         };
+        let maze = &diff.boards[index];
+        let bounds = ctx.size().to_rect();
+        paint_maze(ctx, data, maze, bounds);
+
+        let cell = Size::new(
+            bounds.width() / maze.width as f64,
+            bounds.height() / maze.height as f64,
+        );
+        let tint = data.palette.current.with_alpha(DIFF_ALPHA);
+        for &(x, y) in &diff.differ {
+            let origin = Point::new(x as f64 * cell.width, y as f64 * cell.height);
+            ctx.fill(Rect::from_origin_size(origin, cell), &tint);
+        }
     })
+    .padding(5.0)
+}
+
+// applies the selected tool to a square when it's pressed, and keeps painting
+// walls or mud onto each square the mouse is dragged across with the button
+// held. Holding shift erases instead.
+struct Brush {
+    x: i32,
+    y: i32,
+}
+
+impl<W: Widget<State>> Controller<State, W> for Brush {
+    fn event(
+        &mut self,
+        child: &mut W,
+        ctx: &mut EventCtx,
+        event: &Event,
+        data: &mut State,
+        env: &Env,
+    ) {
+        match event {
+            Event::MouseDown(mouse) if mouse.button.is_left() => {
+                data.press(self.x, self.y, mouse.mods.shift())
+            }
+            Event::MouseMove(mouse) if mouse.buttons.has_left() => {
+                data.paint(self.x, self.y, mouse.mods.shift())
+            }
+            _ => {}
+        }
+
+        child.event(ctx, event, data, env)
+    }
+
+    fn lifecycle(
+        &mut self,
+        child: &mut W,
+        ctx: &mut LifeCycleCtx,
+        event: &LifeCycle,
+        data: &State,
+        env: &Env,
+    ) {
+        // the wall preview follows the mouse on and off the square
+        if let LifeCycle::HotChanged(_) = event {
+            ctx.request_paint();
+        }
+        child.lifecycle(ctx, event, data, env)
+    }
 }
 
 fn gen_square_row(y: i32, width: i32) -> impl Widget<State> {
@@ -248,35 +1110,1666 @@ fn gen_square_row(y: i32, width: i32) -> impl Widget<State> {
     row
 }
 
-fn main() -> Result<(), PlatformError> {
-    let data = State::new();
-    let main_window = WindowDesc::new(ui_builder(&data));
-    AppLauncher::with_window(main_window)
-        .log_to_console()
-        .launch(data)
+// advances an animated search by `steps_per_frame` expansions every
+// `speed_ms`, picking up a new speed from the slider each time the next timer
+// is scheduled
+struct Animator {
+    timer: TimerToken,
 }
 
-fn ui_builder(data: &State) -> impl Widget<State> {
-    let start_button = Button::new("start")
-        .on_click(|_ctx, data: &mut State, _env| {
-            *Arc::make_mut(&mut data.button_state) = ButtonState::Start;
-            data.traverse();
-        })
-        .padding(5.0);
+impl<W: Widget<State>> Controller<State, W> for Animator {
+    fn event(
+        &mut self,
+        child: &mut W,
+        ctx: &mut EventCtx,
+        event: &Event,
+        data: &mut State,
+        env: &Env,
+    ) {
+        match event {
+            // a timer left over from before a pause goes off without stepping
+            Event::Timer(token) if *token == self.timer => {
+                self.timer = TimerToken::INVALID;
+                if let AnimState::Running | AnimState::PathDraw(_) = *data.anim {
+                    data.advance();
+                }
+            }
+            _ => child.event(ctx, event, data, env),
+        }
 
-    let obstacle_button = Button::new("add obstacles")
-        .on_click(|_ctx, data: &mut State, _env| {
-            *Arc::make_mut(&mut data.button_state) = ButtonState::Obstacle;
-        })
-        .padding(5.0);
+        // however the search or replay ended, by finishing, being cancelled
+        // or the board being replaced, there's nothing left to animate but
+        // the path it may have found
+        let over = !data.maze.is_searching() && data.replay.is_none();
+        if over && matches!(*data.anim, AnimState::Running | AnimState::Paused) {
+            data.anim = Arc::new(AnimState::Idle);
+        }
 
-    let new_game_button = Button::new("new game")
-        .on_click(|_ctx, data: &mut State, _env| data.clear())
-        .padding(5.0);
+        let ticking = matches!(*data.anim, AnimState::Running | AnimState::PathDraw(_));
+        if ticking && self.timer == TimerToken::INVALID {
+            self.timer = ctx.request_timer(data.step_interval());
+        }
+    }
+}
 
-    data.gen_board(DEFAULT_HEIGHT, DEFAULT_WIDTH)
-        .with_flex_spacer(2.0)
-        .with_child(start_button)
-        .with_child(obstacle_button)
-        .with_child(new_game_button)
+// takes in what the worker thread reports about a background solve or
+// generation. Its result is only put on the board if the board hasn't been
+// touched since it started, and isn't pre-empted by a newer one.
+struct Worker;
+
+impl<W: Widget<State>> Controller<State, W> for Worker {
+    fn event(
+        &mut self,
+        child: &mut W,
+        ctx: &mut EventCtx,
+        event: &Event,
+        data: &mut State,
+        env: &Env,
+    ) {
+        match event {
+            Event::Command(cmd) if cmd.is(SOLVE_PROGRESS) => {
+                let progress = cmd.get(SOLVE_PROGRESS).unwrap();
+                if is_current(&data.solving, &progress.run) {
+                    data.explored = progress.explored;
+                    data.progress = explored_share(progress.explored, data.open_squares);
+                }
+                ctx.set_handled();
+            }
+            Event::Command(cmd) if cmd.is(SOLVE_DONE) => {
+                let solved = cmd.get(SOLVE_DONE).unwrap();
+                let current = is_current(&data.solving, &solved.run);
+                if current {
+                    data.solving = None;
+                }
+                // a cancelled solve still hands back its partial search, as
+                // long as nothing has started since
+                if (current || data.solving.is_none()) && Arc::ptr_eq(&solved.source, &data.maze) {
+                    data.maze = solved.maze.clone();
+                    data.solve_steps = solved.steps;
+                    data.solve_time = solved.elapsed;
+                    data.solved_with = Arc::new(solved.algorithm.clone());
+                    data.remember_solve();
+                }
+                ctx.set_handled();
+            }
+            Event::Command(cmd) if cmd.is(GENERATE_PROGRESS) => {
+                data.carved(cmd.get(GENERATE_PROGRESS).unwrap());
+                ctx.set_handled();
+            }
+            Event::Command(cmd) if cmd.is(GENERATE_DONE) => {
+                data.generated(cmd.get(GENERATE_DONE).unwrap());
+                ctx.set_handled();
+            }
+            _ => child.event(ctx, event, data, env),
+        }
+    }
+}
+
+// remembers the board size, algorithm, theme and speed for the next run
+struct SaveSettings;
+
+impl<W: Widget<State>> Controller<State, W> for SaveSettings {
+    fn event(
+        &mut self,
+        child: &mut W,
+        ctx: &mut EventCtx,
+        event: &Event,
+        data: &mut State,
+        env: &Env,
+    ) {
+        if let Event::WindowCloseRequested = event {
+            if let Err(err) = Settings::from_state(data).save() {
+                eprintln!("error: couldn't save settings: {}", err);
+            }
+        }
+
+        child.event(ctx, event, data, env)
+    }
+}
+
+// the actions bound to a single key, shown next to the buttons they stand in for
+enum Shortcut {
+    Start,
+    ToggleObstacles,
+    NewGame,
+    Cancel,
+}
+
+// the way an arrow key moves the player
+fn arrow_direction(key: &KeyEvent) -> Option<Direction> {
+    match key.key {
+        KbKey::ArrowUp => Some(Direction::Up),
+        KbKey::ArrowDown => Some(Direction::Down),
+        KbKey::ArrowLeft => Some(Direction::Left),
+        KbKey::ArrowRight => Some(Direction::Right),
+        _ => None,
+    }
+}
+
+fn shortcut(key: &KeyEvent) -> Option<Shortcut> {
+    // leave combinations like cmd+n to the platform
+    if key.mods.ctrl() || key.mods.meta() || key.mods.alt() {
+        return None;
+    }
+
+    match &key.key {
+        KbKey::Character(c) if c == " " => Some(Shortcut::Start),
+        KbKey::Character(c) if c.eq_ignore_ascii_case("o") => Some(Shortcut::ToggleObstacles),
+        KbKey::Character(c) if c.eq_ignore_ascii_case("n") => Some(Shortcut::NewGame),
+        KbKey::Escape => Some(Shortcut::Cancel),
+        _ => None,
+    }
+}
+
+// undo, redo and the single key shortcuts. Key presses only reach focused
+// widgets, so the whole window takes focus as soon as it opens and again
+// whenever the board or a button is clicked.
+struct Shortcuts;
+
+impl<W: Widget<State>> Controller<State, W> for Shortcuts {
+    fn event(
+        &mut self,
+        child: &mut W,
+        ctx: &mut EventCtx,
+        event: &Event,
+        data: &mut State,
+        env: &Env,
+    ) {
+        match event {
+            Event::WindowConnected => ctx.request_focus(),
+            // anything under the mouse that wants focus, like the seed box,
+            // asks after this and takes it instead
+            Event::MouseDown(_) => ctx.request_focus(),
+            Event::KeyDown(key) if HotKey::new(SysMods::Cmd, "z").matches(key) => {
+                data.undo();
+                ctx.set_handled();
+                return;
+            }
+            Event::KeyDown(key)
+                if HotKey::new(SysMods::Cmd, "y").matches(key)
+                    || HotKey::new(SysMods::CmdShift, "Z").matches(key) =>
+            {
+                data.redo();
+                ctx.set_handled();
+                return;
+            }
+            // only while the window itself has focus, so typing a seed
+            // doesn't start a search
+            Event::KeyDown(key) if ctx.is_focused() => {
+                if let (Some(direction), Some(_)) = (arrow_direction(key), data.player) {
+                    data.move_player(direction);
+                    ctx.set_handled();
+                    return;
+                }
+                if let Some(shortcut) = shortcut(key) {
+                    match shortcut {
+                        Shortcut::Start => data.start(ctx.get_external_handle()),
+                        Shortcut::ToggleObstacles => {
+                            let state = match *data.button_state {
+                                ButtonState::Obstacle => ButtonState::NewGame,
+                                _ => ButtonState::Obstacle,
+                            };
+                            data.button_state = Arc::new(state);
+                        }
+                        Shortcut::NewGame => data.clear(),
+                        Shortcut::Cancel => data.cancel(),
+                    }
+                    ctx.set_handled();
+                    return;
+                }
+            }
+            _ => {}
+        }
+
+        child.event(ctx, event, data, env)
+    }
+}
+
+fn algorithm_button(label: &'static str, algorithm: Algorithm) -> impl Widget<State> {
+    let selected = algorithm.clone();
+    Button::dynamic(move |data: &State, _env| {
+        if *data.algorithm == selected {
+            format!("[{}]", label)
+        } else {
+            label.to_string()
+        }
+    })
+    .on_click(move |_ctx, data: &mut State, _env| {
+        data.algorithm = Arc::new(algorithm.clone());
+    })
+    .padding(5.0)
+}
+
+fn generator_button(label: &'static str, generator: MazeGenerator) -> impl Widget<State> {
+    let selected = generator.clone();
+    Button::dynamic(move |data: &State, _env| {
+        if *data.generator == selected {
+            format!("[{}]", label)
+        } else {
+            label.to_string()
+        }
+    })
+    .on_click(move |_ctx, data: &mut State, _env| {
+        data.generator = Arc::new(generator.clone());
+    })
+    .padding(5.0)
+}
+
+fn heuristic_button(label: &'static str, heuristic: Heuristic) -> impl Widget<State> {
+    Button::dynamic(move |data: &State, _env| {
+        if data.maze.heuristic == heuristic {
+            format!("[{}]", label)
+        } else {
+            label.to_string()
+        }
+    })
+    .on_click(move |_ctx, data: &mut State, _env| {
+        if data.maze.heuristic != heuristic {
+            data.maze_mut().heuristic = heuristic;
+        }
+    })
+    .padding(5.0)
+}
+
+fn neighbour_order_button(label: &'static str, order: [Direction; 4]) -> impl Widget<State> {
+    Button::dynamic(move |data: &State, _env| {
+        if data.maze.neighbour_order == order {
+            format!("[{}]", label)
+        } else {
+            label.to_string()
+        }
+    })
+    .on_click(move |_ctx, data: &mut State, _env| {
+        if data.maze.neighbour_order != order {
+            data.maze_mut().neighbour_order = order;
+        }
+    })
+    .padding(5.0)
+}
+
+// a stepper for one of the board dimensions, resizing the board as it changes
+fn dimension_stepper(
+    label: &'static str,
+    get: fn(&State) -> i32,
+    set: fn(&mut State, i32),
+) -> impl Widget<State> {
+    Flex::row()
+        .with_child(Label::new(move |data: &State, _env: &Env| {
+            format!("{}: {}", label, get(data))
+        }))
+        .with_child(
+            Stepper::new()
+                .with_range(MIN_DIMENSION as f64, MAX_DIMENSION as f64)
+                .with_step(1.0)
+                .lens(lens::Map::new(
+                    move |data: &State| get(data) as f64,
+                    move |data: &mut State, value: f64| set(data, value as i32),
+                )),
+        )
+        .padding(5.0)
+}
+
+// where the first square of `kind` is, typed in as a column and row rather
+// than clicked. Entries off the board are pulled back onto its edge, and ones
+// landing on the other endpoint are refused, either way the steppers snap
+// back to where the square really is.
+fn endpoint_row(label: &'static str, kind: SquareKind) -> impl Widget<State> {
+    let axis = |kind: SquareKind, vertical: bool| {
+        let get_kind = kind.clone();
+        Stepper::new()
+            .with_range(0.0, (MAX_DIMENSION - 1) as f64)
+            .with_step(1.0)
+            .lens(lens::Map::new(
+                move |data: &State| {
+                    let (x, y) = data.maze.find_square(get_kind.clone()).unwrap_or((0, 0));
+                    (if vertical { y } else { x }) as f64
+                },
+                move |data: &mut State, value: f64| enter_coordinate(data, &kind, vertical, value),
+            ))
+    };
+
+    let label_kind = kind.clone();
+    Flex::row()
+        .with_child(Label::new(move |data: &State, _env: &Env| {
+            match data.maze.find_square(label_kind.clone()) {
+                Some((x, y)) => format!("{}: ({}, {})", label, x, y),
+                None => format!("{}: none", label),
+            }
+        }))
+        .with_child(axis(kind.clone(), false))
+        .with_child(axis(kind, true))
+        .padding(5.0)
+}
+
+// moves the first square of `kind` along one axis to the typed in `value`,
+// pulled back onto the board if it's off the edge
+fn enter_coordinate(data: &mut State, kind: &SquareKind, vertical: bool, value: f64) {
+    let current = data.maze.find_square(kind.clone()).unwrap_or((0, 0));
+    let (x, y) = if vertical {
+        (current.0, (value as i32).clamp(0, data.maze.height - 1))
+    } else {
+        ((value as i32).clamp(0, data.maze.width - 1), current.1)
+    };
+    if (x, y) != current {
+        let kind = kind.clone();
+        data.edit(|maze| maze.move_endpoint(x, y, kind));
+    }
+}
+
+// an empty seed box means a different board every time
+fn parse_seed(seed: &str) -> Result<Option<u64>, String> {
+    match seed.trim() {
+        "" => Ok(None),
+        seed => seed
+            .parse()
+            .map(Some)
+            .map_err(|_| format!("seed {:?} isn't a whole number", seed)),
+    }
+}
+
+// a checkbox for one of the maze's movement rules
+fn maze_checkbox(
+    label: &'static str,
+    get: fn(&Maze) -> bool,
+    set: fn(&mut Maze, bool),
+) -> impl Widget<State> {
+    Checkbox::new(label)
+        .lens(lens::Map::new(
+            move |data: &State| get(&data.maze),
+            move |data: &mut State, value: bool| {
+                if get(&data.maze) != value {
+                    set(data.maze_mut(), value);
+                }
+            },
+        ))
+        .padding(5.0)
+}
+
+fn main() -> Result<(), PlatformError> {
+    // any arguments run the solver from the command line instead of the window
+    let args: Vec<String> = env::args().skip(1).collect();
+    if !args.is_empty() {
+        process::exit(cli::run(&args));
+    }
+
+    let mut data = State::new();
+    Settings::load().apply(&mut data);
+    let main_window = WindowDesc::new(ui_builder());
+    AppLauncher::with_window(main_window)
+        .log_to_console()
+        .launch(data)
+}
+
+fn ui_builder() -> impl Widget<State> {
+    let start_button = Button::new("start (space)")
+        .on_click(|ctx, data: &mut State, _env| data.start(ctx.get_external_handle()))
+        .padding(5.0);
+
+    let play_button = Button::dynamic(|data: &State, _env| {
+        if data.player.is_some() {
+            "stop playing".to_string()
+        } else {
+            "play (arrow keys)".to_string()
+        }
+    })
+    .on_click(|_ctx, data: &mut State, _env| data.play())
+    .padding(5.0);
+
+    let path_count_row = Flex::row()
+        .with_child(
+            Button::new("count paths")
+                .on_click(|_ctx, data: &mut State, _env| {
+                    data.path_count = Some(Arc::new(data.maze.count_paths(PATH_COUNT_CAP)));
+                })
+                .padding(5.0),
+        )
+        .with_child(Label::new(|data: &State, _env: &Env| {
+            match &data.path_count {
+                Some(count) => count.to_string(),
+                None => String::new(),
+            }
+        }));
+
+    let checkpoint_solve_button = Button::new("solve through checkpoints")
+        .on_click(|_ctx, data: &mut State, _env| data.solve_checkpoints())
+        .padding(5.0);
+
+    let compare_button = Button::new("compare DFS, BFS and A*")
+        .on_click(|_ctx, data: &mut State, _env| data.compare())
+        .padding(5.0);
+
+    let obstacle_button = Button::new("add obstacles (O)")
+        .on_click(|_ctx, data: &mut State, _env| {
+            *Arc::make_mut(&mut data.button_state) = ButtonState::Obstacle;
+        })
+        .padding(5.0);
+
+    let weighted_button = Button::new("add mud")
+        .on_click(|_ctx, data: &mut State, _env| {
+            *Arc::make_mut(&mut data.button_state) = ButtonState::Weighted;
+        })
+        .padding(5.0);
+
+    let erase_button = Button::new("erase")
+        .on_click(|_ctx, data: &mut State, _env| {
+            *Arc::make_mut(&mut data.button_state) = ButtonState::Erase;
+        })
+        .padding(5.0);
+
+    let move_start_button = Button::new("move start")
+        .on_click(|_ctx, data: &mut State, _env| {
+            *Arc::make_mut(&mut data.button_state) = ButtonState::MoveStart;
+        })
+        .padding(5.0);
+
+    let move_end_button = Button::new("move end")
+        .on_click(|_ctx, data: &mut State, _env| {
+            *Arc::make_mut(&mut data.button_state) = ButtonState::MoveEnd;
+        })
+        .padding(5.0);
+
+    let add_end_button = Button::new("add end")
+        .on_click(|_ctx, data: &mut State, _env| {
+            *Arc::make_mut(&mut data.button_state) = ButtonState::AddEnd;
+        })
+        .padding(5.0);
+
+    let lock_button = Button::new("lock squares (shift unlocks)")
+        .on_click(|_ctx, data: &mut State, _env| {
+            *Arc::make_mut(&mut data.button_state) = ButtonState::Lock;
+        })
+        .padding(5.0);
+
+    let portal_button = Button::new("add portals")
+        .on_click(|_ctx, data: &mut State, _env| {
+            *Arc::make_mut(&mut data.button_state) = ButtonState::Portal;
+        })
+        .padding(5.0);
+
+    let checkpoint_button = Button::new("add checkpoints")
+        .on_click(|_ctx, data: &mut State, _env| {
+            *Arc::make_mut(&mut data.button_state) = ButtonState::Checkpoint;
+        })
+        .padding(5.0);
+
+    let algorithm_row = Flex::row()
+        .with_child(algorithm_button("DFS", Algorithm::Dfs))
+        .with_child(algorithm_button("BFS", Algorithm::Bfs))
+        .with_child(algorithm_button("A*", Algorithm::AStar))
+        .with_child(algorithm_button("Dijkstra", Algorithm::Dijkstra))
+        .with_child(algorithm_button("greedy", Algorithm::Greedy))
+        .with_child(algorithm_button("IDDFS", Algorithm::Iddfs))
+        .with_child(algorithm_button("bidirectional", Algorithm::Bidirectional))
+        .with_child(algorithm_button("wall follower", Algorithm::WallFollower));
+
+    let speed_row = Flex::row()
+        .with_child(
+            Slider::new()
+                .with_range(MIN_SPEED_MS, MAX_SPEED_MS)
+                .lens(State::speed_ms),
+        )
+        .with_child(Label::new(|data: &State, _env: &Env| {
+            format!("{:.0} ms per frame", data.speed_ms)
+        }))
+        .with_child(Label::new(|data: &State, _env: &Env| {
+            format!("  {} steps per frame", data.steps_per_frame)
+        }))
+        .with_child(
+            Stepper::new()
+                .with_range(1.0, MAX_STEPS_PER_FRAME as f64)
+                .with_step(1.0)
+                .lens(lens::Map::new(
+                    |data: &State| data.steps_per_frame as f64,
+                    |data: &mut State, value: f64| data.steps_per_frame = value as u32,
+                )),
+        )
+        .padding(5.0);
+
+    let grid_row = Flex::row()
+        .with_child(Checkbox::new("grid lines").lens(State::grid.then(GridStyle::show)))
+        .with_child(
+            Slider::new()
+                .with_range(MIN_GRID_WIDTH, MAX_GRID_WIDTH)
+                .lens(State::grid.then(GridStyle::width))
+                .disabled_if(|data: &State, _| !data.grid.show),
+        )
+        .with_child(Label::new(|data: &State, _env: &Env| {
+            format!("{:.1}px", data.grid.width)
+        }))
+        .padding(5.0);
+
+    let playback_row = Flex::row()
+        .with_child(
+            Button::new("pause")
+                .on_click(|_ctx, data: &mut State, _env| data.pause())
+                .padding(5.0),
+        )
+        .with_child(
+            Button::new("resume")
+                .on_click(|_ctx, data: &mut State, _env| data.resume())
+                .padding(5.0),
+        )
+        .with_child(
+            Button::new("step")
+                .on_click(|_ctx, data: &mut State, _env| data.step_once())
+                .padding(5.0),
+        )
+        .with_child(
+            Button::new("cancel (Esc)")
+                .on_click(|_ctx, data: &mut State, _env| data.cancel())
+                .padding(5.0),
+        )
+        .with_child(
+            Button::new("replay")
+                .on_click(|_ctx, data: &mut State, _env| data.replay())
+                .padding(5.0),
+        );
+
+    let dimension_row = Flex::row()
+        .with_child(dimension_stepper(
+            "width",
+            |data| data.maze.width,
+            |data, width| data.resize(data.maze.height, width),
+        ))
+        .with_child(dimension_stepper(
+            "height",
+            |data| data.maze.height,
+            |data, height| data.resize(height, data.maze.width),
+        ));
+
+    let endpoint_coordinates = Flex::row()
+        .with_child(endpoint_row("start", SquareKind::StartSquare))
+        .with_child(endpoint_row("end", SquareKind::EndSquare));
+
+    // rebuild the grid of squares whenever the dimensions change, keeping
+    // the zoom on large boards and the squares square
+    let board = Letterbox::new(
+        Viewport::new(Inspector::new(ViewSwitcher::new(
+            |data: &State, _env| (data.maze.width, data.maze.height),
+            |_, data: &State, _env| Box::new(data.gen_board(data.maze.height, data.maze.width)),
+        )))
+        .with_minimap(|ctx, data: &State, bounds| paint_maze(ctx, data, &data.maze, bounds)),
+        |data: &State| (data.maze.width, data.maze.height),
+    )
+    .controller(FitWindow);
+
+    // the compared boards sit in a row under the real one
+    let comparison = ViewSwitcher::new(
+        |data: &State, _env| data.comparison.is_some(),
+        |shown, _data: &State, _env| {
+            if !*shown {
+                return Box::new(SizedBox::empty());
+            }
+
+            let mut row = Flex::row();
+            for index in 0..COMPARED.len() {
+                row = row.with_flex_child(comparison_board(index), 1.0);
+            }
+            let close_button = Button::new("close")
+                .on_click(|_ctx, data: &mut State, _env| data.comparison = None)
+                .padding(5.0);
+            Box::new(row.with_child(close_button).fix_height(COMPARISON_HEIGHT))
+        },
+    );
+
+    // the two seeded boards sit side by side under the real one
+    let seed_diff = ViewSwitcher::new(
+        |data: &State, _env| data.seed_diff.is_some(),
+        |shown, _data: &State, _env| {
+            if !*shown {
+                return Box::new(SizedBox::empty());
+            }
+
+            let label = Label::new(|data: &State, _env: &Env| match &data.seed_diff {
+                Some(diff) => format!("{} squares differ", diff.differ.len()),
+                None => String::new(),
+            });
+            let close_button = Button::new("close")
+                .on_click(|_ctx, data: &mut State, _env| data.seed_diff = None)
+                .padding(5.0);
+            let row = Flex::row()
+                .with_flex_child(seed_diff_board(0), 1.0)
+                .with_flex_child(seed_diff_board(1), 1.0)
+                .with_child(Flex::column().with_child(label).with_child(close_button));
+            Box::new(row.fix_height(COMPARISON_HEIGHT))
+        },
+    );
+
+    let generate_button = Button::new("generate maze")
+        .on_click(|ctx, data: &mut State, _env| {
+            let seed = match parse_seed(&data.seed) {
+                Ok(seed) => seed,
+                Err(err) => {
+                    eprintln!("error: {}", err);
+                    return;
+                }
+            };
+
+            let generator = (*data.generator).clone();
+            data.generate(ctx.get_external_handle(), generator, seed);
+        })
+        .padding(5.0);
+
+    let fill_row = Flex::row()
+        .with_child(
+            Button::new("random fill")
+                .on_click(|_ctx, data: &mut State, _env| {
+                    let seed = match parse_seed(&data.seed) {
+                        Ok(seed) => seed,
+                        Err(err) => {
+                            eprintln!("error: {}", err);
+                            return;
+                        }
+                    };
+
+                    let (density, reroll) = (data.density, data.reroll);
+                    data.reset_history();
+                    data.fill_attempts = None;
+                    if reroll {
+                        data.maze_mut()
+                            .random_fill_solvable(density, seed, FILL_ATTEMPTS);
+                    } else {
+                        data.maze_mut().random_fill(density, seed);
+                    }
+                })
+                .padding(5.0),
+        )
+        .with_child(
+            Slider::new()
+                .with_range(0.0, MAX_DENSITY)
+                .lens(State::density),
+        )
+        .with_child(Label::new(|data: &State, _env: &Env| {
+            format!("{:.0}% walls", data.density * 100.0)
+        }))
+        .with_child(
+            Checkbox::new("until solvable")
+                .lens(State::reroll)
+                .padding(5.0),
+        )
+        .with_child(
+            Button::new("solvable fill")
+                .on_click(|_ctx, data: &mut State, _env| {
+                    let seed = match parse_seed(&data.seed) {
+                        Ok(seed) => seed,
+                        Err(err) => {
+                            eprintln!("error: {}", err);
+                            return;
+                        }
+                    };
+
+                    let density = data.density;
+                    data.reset_history();
+                    let attempts = data.maze_mut().generate_solvable_random(density, seed);
+                    data.fill_attempts = Some(attempts);
+                })
+                .padding(5.0),
+        )
+        .with_child(Label::new(|data: &State, _env: &Env| {
+            match data.fill_attempts {
+                Some(1) => "solvable first try".to_string(),
+                Some(attempts) => format!("solvable after {} tries", attempts),
+                None => String::new(),
+            }
+        }));
+
+    // what A* and greedy aim by
+    let heuristic_row = Flex::row()
+        .with_child(Label::new("heuristic:").padding(5.0))
+        .with_child(heuristic_button("octile", Heuristic::Octile))
+        .with_child(heuristic_button("Manhattan", Heuristic::Manhattan))
+        .with_child(heuristic_button("Euclidean", Heuristic::Euclidean))
+        .with_child(heuristic_button("Chebyshev", Heuristic::Chebyshev))
+        .with_child(heuristic_button("zero", Heuristic::Zero));
+
+    // which way depth first search tries first, written as the first letter
+    // of each direction in the order they're tried
+    let neighbour_row = Flex::row()
+        .with_child(Label::new("neighbour order:").padding(5.0))
+        .with_child(neighbour_order_button("RLUD", DEFAULT_NEIGHBOUR_ORDER))
+        .with_child(neighbour_order_button("URDL", Direction::ALL))
+        .with_child(neighbour_order_button(
+            "ULDR",
+            [
+                Direction::Up,
+                Direction::Left,
+                Direction::Down,
+                Direction::Right,
+            ],
+        ))
+        .with_child(neighbour_order_button(
+            "DULR",
+            [
+                Direction::Down,
+                Direction::Up,
+                Direction::Left,
+                Direction::Right,
+            ],
+        ));
+
+    let generator_row = Flex::row()
+        .with_child(generator_button("backtracker", MazeGenerator::Backtracker))
+        .with_child(generator_button("Prim's", MazeGenerator::Prim))
+        .with_child(generator_button("division", MazeGenerator::Division))
+        .with_child(generator_button("Kruskal's", MazeGenerator::Kruskal))
+        .with_child(generator_button("Eller's", MazeGenerator::Eller))
+        .with_child(generator_button(
+            "hunt and kill",
+            MazeGenerator::HuntAndKill,
+        ))
+        .with_child(generator_button("Wilson's", MazeGenerator::Wilson))
+        .with_child(
+            TextBox::new()
+                .with_placeholder("seed")
+                .lens(State::seed)
+                .padding(5.0),
+        )
+        .with_child(generate_button)
+        .with_child(
+            TextBox::new()
+                .with_placeholder("other seed")
+                .lens(State::other_seed)
+                .padding(5.0),
+        )
+        .with_child(
+            Button::new("compare seeds")
+                .on_click(|_ctx, data: &mut State, _env| {
+                    let seeds = match (parse_seed(&data.seed), parse_seed(&data.other_seed)) {
+                        (Ok(a), Ok(b)) => [a, b],
+                        (Err(err), _) | (_, Err(err)) => {
+                            eprintln!("error: {}", err);
+                            return;
+                        }
+                    };
+                    data.compare_seeds(seeds);
+                })
+                .padding(5.0),
+        );
+
+    let movement_row = Flex::row()
+        .with_child(maze_checkbox(
+            "diagonals",
+            |maze| maze.allow_diagonals,
+            |maze, value| maze.allow_diagonals = value,
+        ))
+        .with_child(
+            Slider::new()
+                .with_range(MIN_DIAGONAL_COST, MAX_DIAGONAL_COST)
+                .lens(lens::Map::new(
+                    |data: &State| data.maze.diagonal_cost,
+                    |data: &mut State, cost: f64| {
+                        if cost != data.maze.diagonal_cost {
+                            data.maze_mut().diagonal_cost = cost;
+                        }
+                    },
+                )),
+        )
+        .with_child(Label::new(|data: &State, _env: &Env| {
+            format!("diagonal cost {:.2}", data.maze.diagonal_cost)
+        }))
+        .with_child(
+            Slider::new()
+                .with_range(0.0, MAX_TURN_COST)
+                .lens(lens::Map::new(
+                    |data: &State| data.maze.turn_cost,
+                    |data: &mut State, cost: f64| {
+                        if cost != data.maze.turn_cost {
+                            data.maze_mut().turn_cost = cost;
+                        }
+                    },
+                )),
+        )
+        .with_child(Label::new(|data: &State, _env: &Env| {
+            format!("turn cost {:.1}", data.maze.turn_cost)
+        }))
+        .with_child(maze_checkbox(
+            "cut corners",
+            |maze| maze.allow_corner_cutting,
+            |maze, value| maze.allow_corner_cutting = value,
+        ))
+        .with_child(maze_checkbox(
+            "wrap edges",
+            |maze| maze.wrap,
+            |maze, value| maze.wrap = value,
+        ))
+        .with_child(maze_checkbox(
+            "DFS simple paths",
+            |maze| maze.revisit_policy == RevisitPolicy::CurrentPathOnly,
+            |maze, value| {
+                maze.revisit_policy = if value {
+                    RevisitPolicy::CurrentPathOnly
+                } else {
+                    RevisitPolicy::GlobalVisited
+                }
+            },
+        ));
+
+    let status = Label::new(|data: &State, _env: &Env| {
+        if let Err(err) = data.maze.validate() {
+            format!("Can't search: {}", err)
+        } else if let Some((x, y)) = data.player {
+            if data.maze.get(x, y) == Some(&SquareKind::EndSquare) {
+                format!("You reached the end in {} moves!", data.trail.len() - 1)
+            } else {
+                "Playing, walk to the end with the arrow keys".to_string()
+            }
+        } else if data.generating.is_some() {
+            "Generating a maze in the background, press Esc to stop".to_string()
+        } else if data.solving.is_some() {
+            format!(
+                "Solving in the background, {} squares explored, press Esc to stop",
+                data.explored
+            )
+        } else if data.maze.unsolvable {
+            match &data.checkpoint_error {
+                Some(err) => format!("No path exists, {}", err),
+                None => "No path exists".to_string(),
+            }
+        } else if *data.anim == AnimState::Paused {
+            "Paused".to_string()
+        } else if data.maze.is_searching() {
+            "Searching, press Esc to stop".to_string()
+        } else if data.replay.is_some() {
+            "Replaying the last search, press Esc to stop".to_string()
+        } else {
+            String::new()
+        }
+    })
+    .padding(5.0);
+
+    let stats = Label::new(|data: &State, _env: &Env| {
+        format!(
+            "path length: {}  explored: {}  steps: {}  time: {:.1} ms",
+            data.maze.last_path_len,
+            data.maze.last_explored,
+            data.solve_steps,
+            data.solve_time.as_secs_f64() * 1000.0
+        )
+    })
+    .padding(5.0);
+
+    // the solution spelled out move by move, once there is one
+    let directions = ViewSwitcher::new(
+        |data: &State, _env| data.maze.solved,
+        |solved, _data: &State, _env| {
+            if !*solved {
+                return Box::new(SizedBox::empty());
+            }
+
+            let label = Label::new(|data: &State, _env: &Env| {
+                describe_directions(&path_to_directions(&data.maze.path))
+            })
+            .with_line_break_mode(LineBreaking::WordWrap);
+            Box::new(
+                Scroll::new(label)
+                    .vertical()
+                    .fix_height(DIRECTIONS_HEIGHT)
+                    .padding(5.0),
+            )
+        },
+    );
+
+    let save_button = Button::new("save")
+        .on_click(|_ctx, data: &mut State, _env| {
+            if let Err(err) = data.maze.save_to_file(Path::new(MAZE_FILE)) {
+                eprintln!("error: {}", err);
+            }
+        })
+        .padding(5.0);
+
+    let load_button = Button::new("load")
+        .on_click(|_ctx, data: &mut State, _env| {
+            match data.maze_mut().load_maze(Path::new(MAZE_FILE)) {
+                Ok(()) => data.reset_history(),
+                Err(err) => eprintln!("error: {}", err),
+            }
+        })
+        .padding(5.0);
+
+    let import_button = Button::new("import text")
+        .on_click(|_ctx, data: &mut State, _env| {
+            let imported = fs::read_to_string(ASCII_FILE)
+                .map_err(|err| err.to_string())
+                .and_then(|input| Maze::from_ascii(&input).map_err(|err| err.to_string()));
+            match imported {
+                Ok(imported) => data.import(imported),
+                Err(err) => eprintln!("error: {}", err),
+            }
+        })
+        .padding(5.0);
+
+    // the picture is scaled down to the board's current size
+    let picture_button = Button::new("import picture")
+        .on_click(|_ctx, data: &mut State, _env| {
+            let (width, height) = (data.maze.width as u32, data.maze.height as u32);
+            match Maze::from_image(Path::new(PICTURE_FILE), width, height, PICTURE_THRESHOLD) {
+                Ok(imported) => data.import(imported),
+                Err(err) => eprintln!("error: {}", err),
+            }
+        })
+        .padding(5.0);
+
+    let export_button = Button::new("export text")
+        .on_click(|_ctx, data: &mut State, _env| {
+            if let Err(err) = fs::write(ASCII_FILE, data.maze.to_ascii()) {
+                eprintln!("error: {}", err);
+            }
+        })
+        .padding(5.0);
+
+    let image_button = Button::new("save image")
+        .on_click(|_ctx, data: &mut State, _env| {
+            if let Err(err) =
+                data.maze
+                    .render_png(Path::new(IMAGE_FILE), &data.palette, IMAGE_CELL_PX)
+            {
+                eprintln!("error: {}", err);
+            }
+        })
+        .padding(5.0);
+
+    let undo_button = Button::new("undo")
+        .on_click(|_ctx, data: &mut State, _env| data.undo())
+        .padding(5.0);
+
+    let redo_button = Button::new("redo")
+        .on_click(|_ctx, data: &mut State, _env| data.redo())
+        .padding(5.0);
+
+    let snapshot_row = Flex::row()
+        .with_child(
+            Button::new("snapshot")
+                .on_click(|_ctx, data: &mut State, _env| {
+                    let snapshot = data.snapshot();
+                    Arc::make_mut(&mut data.snapshots).push(snapshot)
+                })
+                .padding(5.0),
+        )
+        .with_child(
+            Button::new("restore snapshot")
+                // the latest snapshot is kept, so it can be gone back to again
+                .on_click(|_ctx, data: &mut State, _env| {
+                    if let Some(snapshot) = data.snapshots.latest().cloned() {
+                        data.restore(&snapshot)
+                    }
+                })
+                .padding(5.0),
+        )
+        .with_child(
+            Button::new("drop snapshot")
+                .on_click(|_ctx, data: &mut State, _env| {
+                    Arc::make_mut(&mut data.snapshots).discard()
+                })
+                .padding(5.0),
+        )
+        .with_child(Label::new(|data: &State, _env: &Env| {
+            format!(
+                "{} of {} snapshots kept",
+                data.snapshots.len(),
+                SNAPSHOT_SLOTS
+            )
+        }));
+
+    let clear_walls_button = Button::new("clear walls")
+        .on_click(|_ctx, data: &mut State, _env| data.edit(|maze| maze.clear_walls()))
+        .padding(5.0);
+
+    let flood_button = Button::new("flood")
+        .on_click(|_ctx, data: &mut State, _env| {
+            data.maze_mut().flood_fill_from_start();
+        })
+        .padding(5.0);
+
+    let fill_button = Button::new("fill dead ends")
+        .on_click(|_ctx, data: &mut State, _env| {
+            data.maze_mut().solve_dead_end_fill();
+        })
+        .padding(5.0);
+
+    let clear_path_button = Button::new("clear path")
+        .on_click(|_ctx, data: &mut State, _env| data.maze_mut().clear_solution())
+        .padding(5.0);
+
+    let fit_button = Button::new("fit window")
+        .on_click(|ctx, _data: &mut State, _env| ctx.submit_command(FIT_WINDOW))
+        .padding(5.0);
+
+    let report_button = Button::new("save report")
+        .on_click(|_ctx, data: &mut State, _env| {
+            if let Err(err) = data.solve_report().save(Path::new(REPORT_FILE)) {
+                eprintln!("error: {}", err);
+            }
+        })
+        .padding(5.0);
+
+    let theme_button = Button::new("toggle theme")
+        .on_click(|_ctx, data: &mut State, _env| {
+            let palette = if data.palette.dark {
+                Palette::light()
+            } else {
+                Palette::dark()
+            };
+            data.palette = Arc::new(palette);
+        })
+        .padding(5.0);
+
+    let new_game_button = Button::new("new game (N)")
+        .on_click(|_ctx, data: &mut State, _env| data.clear())
+        .padding(5.0);
+
+    let default_button = Button::new("default maze")
+        .on_click(|_ctx, data: &mut State, _env| data.load_default())
+        .padding(5.0);
+
+    // only there while a search runs or a maze is being carved
+    let progress_bar = ViewSwitcher::new(
+        |data: &State, _env| {
+            data.solving.is_some() || data.generating.is_some() || data.maze.is_searching()
+        },
+        |shown, _data: &State, _env| {
+            if !*shown {
+                return Box::new(SizedBox::empty());
+            }
+            Box::new(ProgressBar::new().lens(State::progress).padding(5.0))
+        },
+    );
+
+    Flex::column()
+        .with_flex_child(board, BOARD_FLEX)
+        .with_child(status)
+        .with_child(progress_bar)
+        .with_child(stats)
+        .with_child(directions)
+        .with_child(comparison)
+        .with_child(seed_diff)
+        .with_flex_spacer(SPACER_FLEX)
+        .with_child(dimension_row)
+        .with_child(endpoint_coordinates)
+        .with_child(algorithm_row)
+        .with_child(heuristic_row)
+        .with_child(neighbour_row)
+        .with_child(start_button)
+        .with_child(checkpoint_solve_button)
+        .with_child(compare_button)
+        .with_child(play_button)
+        .with_child(path_count_row)
+        .with_child(obstacle_button)
+        .with_child(weighted_button)
+        .with_child(erase_button)
+        .with_child(move_start_button)
+        .with_child(move_end_button)
+        .with_child(add_end_button)
+        .with_child(portal_button)
+        .with_child(checkpoint_button)
+        .with_child(lock_button)
+        .with_child(movement_row)
+        .with_child(Checkbox::new("animate").lens(State::animate).padding(5.0))
+        .with_child(
+            Checkbox::new("distances")
+                .lens(State::show_distances)
+                .padding(5.0),
+        )
+        .with_child(Checkbox::new("inspect").lens(State::inspect).padding(5.0))
+        .with_child(
+            Checkbox::new("solution only")
+                .lens(State::hide_explored)
+                .padding(5.0),
+        )
+        .with_child(
+            Checkbox::new("reachable when stuck")
+                .lens(State::show_reachable)
+                .padding(5.0),
+        )
+        .with_child(grid_row)
+        .with_child(speed_row)
+        .with_child(playback_row)
+        .with_child(generator_row)
+        .with_child(fill_row)
+        .with_child(
+            Flex::row()
+                .with_child(save_button)
+                .with_child(load_button)
+                .with_child(import_button)
+                .with_child(picture_button)
+                .with_child(export_button)
+                .with_child(image_button)
+                .with_child(report_button)
+                .with_child(theme_button)
+                .with_child(fit_button),
+        )
+        .with_child(
+            Flex::row()
+                .with_child(undo_button)
+                .with_child(redo_button)
+                .with_child(clear_walls_button)
+                .with_child(flood_button)
+                .with_child(fill_button)
+                .with_child(clear_path_button)
+                .with_child(new_game_button)
+                .with_child(default_button),
+        )
+        .with_child(snapshot_row)
+        .controller(Shortcuts)
+        .controller(SaveSettings)
+        .controller(Worker)
+        .controller(Animator {
+            timer: TimerToken::INVALID,
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use druid::Modifiers;
+    use std::sync::mpsc;
+    use worker::GenerateReport;
+
+    #[test]
+    fn step_interval_clamps_the_speed() {
+        let mut state = State::new();
+        assert_eq!(state.step_interval(), Duration::from_millis(100));
+
+        state.speed_ms = 0.0;
+        assert_eq!(
+            state.step_interval(),
+            Duration::from_millis(MIN_SPEED_MS as u64)
+        );
+        state.speed_ms = 1e9;
+        assert_eq!(
+            state.step_interval(),
+            Duration::from_millis(MAX_SPEED_MS as u64)
+        );
+    }
+
+    #[test]
+    fn resizing_to_three_by_three_gives_three_rows() {
+        let mut state = State::new();
+        state.resize(3, 3);
+
+        // the board widget lays out a row of squares for each row of the maze
+        assert_eq!(state.maze.squares.len(), 3);
+        assert!(state.maze.squares.iter().all(|row| row.len() == 3));
+        let _board = state.gen_board(state.maze.height, state.maze.width);
+    }
+
+    #[test]
+    fn dragging_across_a_row_paints_and_erases_walls() {
+        let mut state = State::new();
+        state.button_state = Arc::new(ButtonState::Obstacle);
+        for x in 0..DEFAULT_WIDTH {
+            state.paint(x, 5, false);
+        }
+        // the stroke passes over the start and end
+        assert_eq!(state.maze.find_squares(SquareKind::Obstacle).len(), 8);
+        assert_eq!(state.maze.get(1, 5), Some(&SquareKind::StartSquare));
+
+        for x in 0..4 {
+            state.paint(x, 5, true);
+        }
+        assert_eq!(state.maze.find_squares(SquareKind::Obstacle).len(), 5);
+        assert_eq!(state.maze.get(2, 5), Some(&SquareKind::Init));
+    }
+
+    #[test]
+    fn keys_map_to_their_shortcuts() {
+        let press = |mods, key: &str| {
+            let key = match key {
+                "Escape" => KbKey::Escape,
+                key => KbKey::Character(key.to_string()),
+            };
+            shortcut(&KeyEvent::for_test(mods, key))
+        };
+        let none = Modifiers::empty();
+
+        assert!(matches!(press(none, " "), Some(Shortcut::Start)));
+        assert!(matches!(press(none, "o"), Some(Shortcut::ToggleObstacles)));
+        assert!(matches!(
+            press(Modifiers::SHIFT, "N"),
+            Some(Shortcut::NewGame)
+        ));
+        assert!(matches!(press(none, "Escape"), Some(Shortcut::Cancel)));
+        assert!(press(none, "x").is_none());
+        // left to the platform
+        assert!(press(Modifiers::CONTROL, "n").is_none());
+    }
+
+    #[test]
+    fn a_manual_step_while_paused_expands_one_square() {
+        let mut state = State::new();
+        state.algorithm = Arc::new(Algorithm::Bfs);
+        state.step_once();
+        assert!(*state.anim == AnimState::Paused);
+
+        state.resume();
+        assert!(*state.anim == AnimState::Running);
+        state.advance();
+        state.pause();
+        assert!(*state.anim == AnimState::Paused);
+
+        let explored = state.maze.last_explored;
+        state.step_once();
+        assert_eq!(state.maze.last_explored, explored + 1);
+        assert!(*state.anim == AnimState::Paused);
+
+        state.resume();
+        assert!(*state.anim == AnimState::Running);
+        state.advance();
+        assert_eq!(state.maze.last_explored, explored + 2);
+    }
+
+    #[test]
+    fn each_tick_expands_steps_per_frame_squares() {
+        let mut state = State::new();
+        state.algorithm = Arc::new(Algorithm::Bfs);
+        state.steps_per_frame = 5;
+        state.step_once();
+        state.resume();
+
+        let explored = state.maze.last_explored;
+        state.advance();
+        assert_eq!(state.maze.last_explored, explored + 5);
+        state.advance();
+        assert_eq!(state.maze.last_explored, explored + 10);
+    }
+
+    #[test]
+    fn squares_are_painted_in_the_palettes_colors() {
+        let shade = |n: u8| Color::rgb8(n, 0, 0);
+        let palette = Palette {
+            init: shade(1),
+            obstacle: shade(2),
+            possible_path: shade(3),
+            frontier: shade(4),
+            backward_path: shade(5),
+            filled: shade(6),
+            solution_path: shade(7),
+            start: shade(8),
+            end: shade(9),
+            portal: shade(10),
+            checkpoint: shade(11),
+            ..Palette::dark()
+        };
+        let kinds = [
+            (SquareKind::Init, shade(1)),
+            (SquareKind::Obstacle, shade(2)),
+            (SquareKind::PossiblePath, shade(3)),
+            (SquareKind::Frontier, shade(4)),
+            (SquareKind::BackwardPath, shade(5)),
+            (SquareKind::Filled, shade(6)),
+            (SquareKind::SolutionPath, shade(7)),
+            (SquareKind::StartSquare, shade(8)),
+            (SquareKind::EndSquare, shade(9)),
+            (SquareKind::Portal(0), shade(10)),
+            (SquareKind::Checkpoint(0), shade(11)),
+        ];
+
+        let mut maze = Maze::new(3, 11);
+        for (x, (kind, _)) in kinds.iter().enumerate() {
+            maze.set(x as i32, 1, kind.clone());
+        }
+        for (x, (kind, color)) in kinds.iter().enumerate() {
+            let painted = square_color(&palette, &maze, x as i32, 1, false);
+            assert!(painted == *color, "{:?}", kind);
+        }
+    }
+
+    #[test]
+    fn typing_in_the_start_moves_it_there() {
+        let mut state = State::new();
+        enter_coordinate(&mut state, &SquareKind::StartSquare, false, 2.0);
+        enter_coordinate(&mut state, &SquareKind::StartSquare, true, 2.0);
+        assert_eq!(
+            state.maze.find_squares(SquareKind::StartSquare),
+            vec![(2, 2)]
+        );
+
+        // off the board is pulled back onto its edge
+        enter_coordinate(&mut state, &SquareKind::StartSquare, false, 500.0);
+        let edge = state.maze.width - 1;
+        assert_eq!(
+            state.maze.find_squares(SquareKind::StartSquare),
+            vec![(edge, 2)]
+        );
+    }
+
+    #[test]
+    fn cancelling_a_running_search_keeps_what_it_explored() {
+        let mut state = State::new();
+        state.algorithm = Arc::new(Algorithm::Bfs);
+        state.step_once();
+        state.resume();
+        state.advance();
+        assert!(*state.anim == AnimState::Running);
+
+        let squares = state.maze.squares.clone();
+        let explored = state.maze.last_explored;
+        state.cancel();
+        assert!(*state.anim == AnimState::Idle);
+        assert!(!state.maze.is_searching());
+        assert!(state.maze.squares == squares);
+        assert_eq!(state.maze.last_explored, explored);
+        assert!(!state.maze.find_squares(SquareKind::Frontier).is_empty());
+    }
+
+    #[test]
+    fn comparing_solves_copies_and_leaves_the_board_alone() {
+        let mut state = State::new();
+        state.paint(4, 5, false);
+        let squares = state.maze.squares.clone();
+
+        state.compare();
+        assert!(state.maze.squares == squares);
+        assert!(!state.maze.solved);
+        assert_eq!(state.maze.last_explored, 0);
+
+        let solved = state.comparison.as_ref().unwrap();
+        assert_eq!(solved.len(), COMPARED.len());
+        for maze in solved.iter() {
+            assert!(maze.solved);
+        }
+    }
+
+    #[test]
+    fn bfs_takes_the_same_number_of_steps_every_time() {
+        let mut state = State::new();
+        state.algorithm = Arc::new(Algorithm::Bfs);
+        state.solve();
+        assert_eq!(state.solve_steps, 59);
+        state.solve();
+        assert_eq!(state.solve_steps, 59);
+    }
+
+    #[test]
+    fn replaying_a_bfs_explores_the_same_squares() {
+        let mut state = State::new();
+        state.algorithm = Arc::new(Algorithm::Bfs);
+        state.paint(4, 5, false);
+        state.solve();
+        let finished = state.maze.clone();
+        let mut explored = finished.find_squares(SquareKind::PossiblePath);
+        explored.extend(finished.find_squares(SquareKind::SolutionPath));
+        explored.sort_unstable();
+
+        state.replay();
+        assert!(state.maze.find_squares(SquareKind::PossiblePath).is_empty());
+        // everything but the last expansion, which puts the finished board
+        // back
+        let steps = state.record.len() as u32;
+        state.replay_steps(steps - 1);
+        let mut replayed = state.maze.find_squares(SquareKind::PossiblePath);
+        replayed.push(*state.record.last().unwrap());
+        replayed.sort_unstable();
+        assert_eq!(replayed, explored);
+
+        state.replay_steps(1);
+        assert!(state.replay.is_none());
+        assert!(state.maze.squares == finished.squares);
+    }
+
+    #[test]
+    fn importing_a_board_keeps_the_movement_rules() {
+        let mut state = State::new();
+        state.maze_mut().heuristic = Heuristic::Zero;
+        state.maze_mut().turn_cost = 3.0;
+        let order = [
+            Direction::Down,
+            Direction::Up,
+            Direction::Left,
+            Direction::Right,
+        ];
+        state.maze_mut().neighbour_order = order;
+        state.import(Maze::from_ascii("S  \n # \n  E\n").unwrap());
+        assert_eq!(state.maze.heuristic, Heuristic::Zero);
+        assert_eq!(state.maze.turn_cost, 3.0);
+        assert_eq!(state.maze.neighbour_order, order);
+    }
+
+    #[test]
+    fn one_bfs_step_marks_the_first_square_dequeued_as_current() {
+        let mut state = State::new();
+        state.algorithm = Arc::new(Algorithm::Bfs);
+        state.step_once();
+
+        // moves are listed last first for depth first search's stack, so the
+        // queue takes the one below the start first
+        let (x, y) = state.maze.find_square(SquareKind::StartSquare).unwrap();
+        assert_eq!(state.current, Some((x, y + 1)));
+        assert_eq!(state.maze.expanded, vec![(x, y + 1)]);
+    }
+
+    #[test]
+    fn erasing_clears_a_wall_but_not_the_start() {
+        let mut state = State::new();
+        state.button_state = Arc::new(ButtonState::Obstacle);
+        state.paint(3, 3, false);
+        assert_eq!(state.maze.get(3, 3), Some(&SquareKind::Obstacle));
+
+        state.button_state = Arc::new(ButtonState::Erase);
+        state.paint(3, 3, false);
+        assert_eq!(state.maze.get(3, 3), Some(&SquareKind::Init));
+
+        let (x, y) = state.maze.find_square(SquareKind::StartSquare).unwrap();
+        state.paint(x, y, false);
+        assert_eq!(state.maze.get(x, y), Some(&SquareKind::StartSquare));
+    }
+
+    #[test]
+    fn a_report_credits_the_algorithm_that_ran_the_solve() {
+        let mut state = State::new();
+        state.import(Maze::from_ascii("S  \n # \n  E\n").unwrap());
+        state.algorithm = Arc::new(Algorithm::Bfs);
+        state.solve();
+        state.algorithm = Arc::new(Algorithm::AStar);
+
+        let json = state.solve_report().to_json().unwrap();
+        let report: SolveReport = serde_json::from_str(&json).unwrap();
+        assert_eq!(report.algorithm, Algorithm::Bfs);
+        assert_eq!(report.solvable, Some(true));
+        assert_eq!(report.path_length, 5);
+        assert_eq!(report.path, vec![(0, 0), (0, 1), (0, 2), (1, 2), (2, 2)]);
+        assert_eq!(report.explored, 6);
+        assert!(report.elapsed_ms >= 0.0);
+    }
+
+    #[test]
+    fn loading_the_default_board_undoes_the_size_and_edits() {
+        let mut state = State::new();
+        state.resize(40, 60);
+        state
+            .maze_mut()
+            .move_endpoint(30, 20, SquareKind::StartSquare);
+        state.maze_mut().set(2, 2, SquareKind::Obstacle);
+
+        state.load_default();
+        let maze = &state.maze;
+        assert_eq!((maze.height, maze.width), (DEFAULT_HEIGHT, DEFAULT_WIDTH));
+        assert_eq!(
+            maze.find_square(SquareKind::StartSquare),
+            Some((pollen::DEFAULT_START_X, pollen::DEFAULT_START_Y))
+        );
+        assert_eq!(
+            maze.find_square(SquareKind::EndSquare),
+            Some((pollen::DEFAULT_END_X, pollen::DEFAULT_END_Y))
+        );
+        assert_eq!(maze.get(2, 2), Some(&SquareKind::Init));
+    }
+
+    #[test]
+    fn only_the_hovered_open_square_previews_a_wall() {
+        let mut state = State::new();
+        state.button_state = Arc::new(ButtonState::Obstacle);
+        state.maze_mut().set(2, 2, SquareKind::Obstacle);
+        assert!(previews_wall(&state, 3, 3, true));
+        // the preview goes once the mouse leaves
+        assert!(!previews_wall(&state, 3, 3, false));
+        assert!(!previews_wall(&state, 2, 2, true));
+        assert!(!previews_wall(&state, 1, 5, true));
+
+        state.button_state = Arc::new(ButtonState::Start);
+        assert!(!previews_wall(&state, 3, 3, true));
+    }
+
+    #[test]
+    fn the_path_is_drawn_a_square_per_frame() {
+        let mut state = State::new();
+        state.import(Maze::from_ascii("S  \n # \n  E\n").unwrap());
+        state.algorithm = Arc::new(Algorithm::Bfs);
+        let path = state.solve();
+        assert_eq!(path.len(), 5);
+
+        state.begin_path_draw();
+        assert!(*state.anim == AnimState::PathDraw(0));
+        assert_eq!(state.maze.get(0, 1), Some(&SquareKind::PossiblePath));
+        for (drawn, &(x, y)) in path.iter().enumerate().skip(1) {
+            state.advance();
+            assert!(*state.anim == AnimState::PathDraw(drawn));
+            // the square the next frame draws is still waiting
+            assert_ne!(state.maze.get(x, y), Some(&SquareKind::SolutionPath));
+        }
+        assert_eq!(state.maze.get(1, 2), Some(&SquareKind::SolutionPath));
+
+        state.advance();
+        assert!(*state.anim == AnimState::Idle);
+        for &(x, y) in &path[1..path.len() - 1] {
+            assert_eq!(state.maze.get(x, y), Some(&SquareKind::SolutionPath));
+        }
+    }
+
+    #[test]
+    fn hiding_the_explored_squares_paints_them_open() {
+        let palette = Palette::light();
+        let mut maze = Maze::from_ascii("S  \n # \n  E\n").unwrap();
+        maze.run(Algorithm::Bfs);
+        maze.mark_square(2, 0, SquareKind::PossiblePath);
+
+        assert!(square_color(&palette, &maze, 2, 0, false) == palette.possible_path);
+        assert!(square_color(&palette, &maze, 2, 0, true) == palette.init);
+        // the squares keep what the search made them, and the path still shows
+        assert_eq!(maze.get(2, 0), Some(&SquareKind::PossiblePath));
+        assert!(square_color(&palette, &maze, 0, 1, true) == palette.solution_path);
+    }
+
+    #[test]
+    fn the_arrow_keys_walk_the_player_to_the_end() {
+        let mut state = State::new();
+        state.import(Maze::from_ascii("S #\n# #\n  E\n").unwrap());
+        state.play();
+        let mut press = |key: KbKey| {
+            let event = KeyEvent::for_test(Modifiers::empty(), key);
+            state.move_player(arrow_direction(&event).unwrap());
+            state.player
+        };
+
+        // walls and the edge of the board stop it where it is
+        assert_eq!(press(KbKey::ArrowDown), Some((0, 0)));
+        assert_eq!(press(KbKey::ArrowUp), Some((0, 0)));
+        assert_eq!(press(KbKey::ArrowRight), Some((1, 0)));
+        assert_eq!(press(KbKey::ArrowRight), Some((1, 0)));
+        assert_eq!(press(KbKey::ArrowDown), Some((1, 1)));
+        assert_eq!(press(KbKey::ArrowDown), Some((1, 2)));
+        assert_eq!(press(KbKey::ArrowRight), Some((2, 2)));
+        // and it stays put on the end once it gets there
+        assert_eq!(press(KbKey::ArrowLeft), Some((2, 2)));
+
+        assert_eq!(*state.trail, vec![(0, 0), (1, 0), (1, 1), (1, 2), (2, 2)]);
+    }
+
+    #[test]
+    fn progress_only_climbs_while_a_solve_runs() {
+        let mut state = State::new();
+        state.import(Maze::new(20, 20));
+        // far enough from the start that the search covers most of the board
+        state
+            .maze_mut()
+            .move_endpoint(19, 19, SquareKind::EndSquare);
+        state.algorithm = Arc::new(Algorithm::Bfs);
+        state.step_once();
+
+        let mut last = state.progress;
+        assert!(last > 0.0);
+        while state.maze.is_searching() {
+            state.step_once();
+            assert!(state.progress >= last);
+            assert!(state.progress <= 1.0);
+            last = state.progress;
+        }
+        assert!(state.maze.solved);
+        assert!(last > 0.5);
+    }
+
+    #[test]
+    fn progress_climbs_from_nothing_to_all_while_a_maze_generates() {
+        let mut state = State::new();
+        state.import(Maze::new(100, 100));
+        let source = state.maze.clone();
+        let (sender, reports) = mpsc::channel();
+        let post = move |report| sender.send(report).is_ok();
+        let run = worker::generate_with(source.clone(), MazeGenerator::Wilson, Some(2), post);
+        state.generating = Some(run);
+
+        let mut last = state.progress;
+        assert_eq!(last, 0.0);
+        loop {
+            match reports.recv_timeout(Duration::from_secs(60)).unwrap() {
+                GenerateReport::Progress(carving) => {
+                    state.carved(&carving);
+                    assert!(state.progress >= last);
+                    assert!(state.progress <= 1.0);
+                    last = state.progress;
+                }
+                GenerateReport::Done(generated) => {
+                    state.generated(&generated);
+                    break;
+                }
+            }
+        }
+        assert!(last > 0.9);
+        assert_eq!(state.progress, 1.0);
+        assert!(state.generating.is_none());
+        assert!(!Arc::ptr_eq(&state.maze, &source));
+        assert!(state.maze.validate().is_ok());
+    }
+
+    #[test]
+    fn restoring_a_snapshot_undoes_the_edits_since() {
+        let mut state = State::new();
+        state.maze_mut().set(3, 3, SquareKind::Obstacle);
+        state.maze_mut().set_square(4, 4, SquareKind::Init, 5);
+        let original = state.maze.clone();
+        let snapshot = state.snapshot();
+
+        state.resize(30, 40);
+        state.maze_mut().set(6, 6, SquareKind::Obstacle);
+        state.maze_mut().set_locked(1, 1, true);
+        state.solve();
+        state.restore(&snapshot);
+
+        let maze = &state.maze;
+        assert_eq!((maze.height, maze.width), (original.height, original.width));
+        assert_eq!(maze.to_ascii(), original.to_ascii());
+        assert_eq!(maze.cost, original.cost);
+        assert_eq!(maze.locked, original.locked);
+        assert!(!maze.solved);
+    }
+
+    #[test]
+    fn with_diagonals_the_far_corner_is_a_chebyshev_distance_away() {
+        let mut state = State::new();
+        state.import(Maze::from_spec(7, 5, (0, 0), (6, 4), &[]).unwrap());
+        state.maze_mut().allow_diagonals = true;
+        state.show_distances = true;
+        // six moves along and four down, four of them diagonal, whichever
+        // search ran, even those that pay more for a diagonal step
+        for algorithm in [Algorithm::Bfs, Algorithm::AStar, Algorithm::Dijkstra] {
+            state.algorithm = Arc::new(algorithm);
+            state.solve();
+            assert_eq!(square_label(&state, 6, 4), Some("6".to_string()));
+        }
+        assert!(state.maze.dist[4][6].unwrap() > 6.0);
+
+        state.maze_mut().allow_diagonals = false;
+        state.solve();
+        assert_eq!(square_label(&state, 6, 4), Some("10".to_string()));
+        assert_eq!(square_label(&state, 0, 0), Some("0".to_string()));
+    }
 }
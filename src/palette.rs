@@ -0,0 +1,144 @@
+use druid::{Color, Data, Lens};
+use pollen::SquareKind;
+use serde::{Deserialize, Serialize};
+
+use crate::{DEFAULT_GRID_WIDTH, WEIGHTED_COST};
+
+// the colors the board is drawn in, shared by the painter and the PNG renderer
+#[derive(Clone, PartialEq)]
+pub struct Palette {
+    // which preset this is, so the theme button knows which to switch to
+    pub dark: bool,
+    pub init: Color,
+    pub obstacle: Color,
+    pub possible_path: Color,
+    pub frontier: Color,
+    pub backward_path: Color,
+    pub filled: Color,
+    pub solution_path: Color,
+    pub start: Color,
+    pub end: Color,
+    pub portal: Color,
+    pub checkpoint: Color,
+    // the outline around the square an animated search just expanded
+    pub current: Color,
+    // what the heaviest mud fades to from `init`
+    pub mud: Color,
+    // the lines between squares and the distances written inside them
+    pub grid: Color,
+    pub text: Color,
+}
+
+impl Palette {
+    pub fn light() -> Palette {
+        Palette {
+            dark: false,
+            init: Color::WHITE,
+            obstacle: Color::BLACK,
+            possible_path: Color::WHITE,
+            frontier: Color::rgb8(0xb3, 0xd9, 0xff),
+            backward_path: Color::rgb8(0xee, 0xe4, 0xf4),
+            filled: Color::grey8(0xc0),
+            solution_path: Color::YELLOW,
+            start: Color::GREEN,
+            end: Color::PURPLE,
+            portal: Color::rgb8(0xff, 0x8c, 0x00),
+            checkpoint: Color::rgb8(0x40, 0xc0, 0xc0),
+            current: Color::rgb8(0xff, 0x45, 0x00),
+            mud: Color::rgb(0.6, 0.4, 0.2),
+            grid: Color::BLACK,
+            text: Color::BLACK,
+        }
+    }
+    pub fn dark() -> Palette {
+        Palette {
+            dark: true,
+            init: Color::rgb8(0x2b, 0x2b, 0x33),
+            obstacle: Color::rgb8(0x0d, 0x0d, 0x10),
+            possible_path: Color::rgb8(0x3c, 0x3c, 0x4a),
+            frontier: Color::rgb8(0x23, 0x50, 0x7a),
+            backward_path: Color::rgb8(0x4a, 0x3a, 0x55),
+            filled: Color::rgb8(0x1c, 0x1c, 0x22),
+            solution_path: Color::rgb8(0xe0, 0xb0, 0x00),
+            start: Color::rgb8(0x2e, 0x9e, 0x4f),
+            end: Color::rgb8(0x9b, 0x59, 0xb6),
+            portal: Color::rgb8(0xd3, 0x7a, 0x1f),
+            checkpoint: Color::rgb8(0x2a, 0x8a, 0x8a),
+            current: Color::rgb8(0xff, 0x6a, 0x2b),
+            mud: Color::rgb8(0x7a, 0x52, 0x30),
+            grid: Color::rgb8(0x10, 0x10, 0x14),
+            text: Color::WHITE,
+        }
+    }
+    pub fn color(&self, kind: &SquareKind) -> Color {
+        match kind {
+            SquareKind::Init => self.init,
+            SquareKind::Obstacle => self.obstacle,
+            SquareKind::PossiblePath => self.possible_path,
+            SquareKind::Frontier => self.frontier,
+            SquareKind::BackwardPath => self.backward_path,
+            SquareKind::Filled => self.filled,
+            SquareKind::SolutionPath => self.solution_path,
+            SquareKind::StartSquare => self.start,
+            SquareKind::EndSquare => self.end,
+            SquareKind::Portal(_) => self.portal,
+            SquareKind::Checkpoint(_) => self.checkpoint,
+        }
+    }
+    // shade weighted squares from `init` towards `mud` the more they cost to
+    // cross
+    pub fn weighted(&self, cost: u32) -> Color {
+        let intensity = cost.min(WEIGHTED_COST * 2) as f64 / (WEIGHTED_COST * 2) as f64;
+        let (r0, g0, b0, _) = self.init.as_rgba();
+        let (r1, g1, b1, _) = self.mud.as_rgba();
+        Color::rgb(
+            r0 + (r1 - r0) * intensity,
+            g0 + (g1 - g0) * intensity,
+            b0 + (b1 - b0) * intensity,
+        )
+    }
+}
+
+// the lines the painter strokes around each square
+#[derive(Clone, Data, Lens, PartialEq, Serialize, Deserialize)]
+pub struct GridStyle {
+    pub show: bool,
+    pub width: f64,
+}
+
+impl Default for GridStyle {
+    fn default() -> Self {
+        GridStyle {
+            show: true,
+            width: DEFAULT_GRID_WIDTH,
+        }
+    }
+}
+
+impl GridStyle {
+    // how thick a line to stroke around each square, if any
+    pub fn stroke_width(&self) -> Option<f64> {
+        if self.show {
+            Some(self.width)
+        } else {
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn grid_lines_are_only_stroked_when_shown() {
+        let mut grid = GridStyle {
+            show: true,
+            width: 2.5,
+        };
+        assert_eq!(grid.stroke_width(), Some(2.5));
+
+        grid.show = false;
+        assert_eq!(grid.stroke_width(), None);
+    }
+}
@@ -0,0 +1,414 @@
+use crate::{Maze, SquareKind};
+use std::fmt;
+
+// what's wrong with a path, pointing at the first square along it that breaks
+// the rules
+#[derive(Debug, PartialEq)]
+pub enum PathError {
+    Empty,
+    WrongStart,
+    WrongEnd,
+    OutOfBounds { index: usize },
+    Obstacle { index: usize },
+    // the square at `index` isn't a single move from the one before it, by
+    // jumping too far or cutting between two walls
+    IllegalMove { index: usize },
+}
+
+impl fmt::Display for PathError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            PathError::Empty => write!(f, "path is empty"),
+            PathError::WrongStart => write!(f, "path doesn't begin at the start square"),
+            PathError::WrongEnd => write!(f, "path doesn't finish on an end square"),
+            PathError::OutOfBounds { index } => write!(f, "square {} is off the board", index),
+            PathError::Obstacle { index } => write!(f, "square {} is a wall", index),
+            PathError::IllegalMove { index } => {
+                write!(f, "square {} can't be reached from the one before", index)
+            }
+        }
+    }
+}
+
+impl std::error::Error for PathError {}
+
+// how many squares `count_paths` steps onto before giving up, since a board
+// with few routes to the end can still have any number of dead ends to try
+const COUNT_STEPS: usize = 1_000_000;
+
+/// How many routes `count_paths` found from the start to an end.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum PathCount {
+    /// Every route there is.
+    Exact(usize),
+    /// At least this many, the count having stopped before trying them all.
+    Capped(usize),
+}
+
+impl fmt::Display for PathCount {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let (prefix, count) = match self {
+            PathCount::Exact(count) => ("", count),
+            PathCount::Capped(count) => ("at least ", count),
+        };
+        let noun = if *count == 1 { "path" } else { "paths" };
+        write!(f, "{}{} {}", prefix, count, noun)
+    }
+}
+
+// which way a single move along a path goes
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Direction {
+    Up,
+    Down,
+    Left,
+    Right,
+    UpLeft,
+    UpRight,
+    DownLeft,
+    DownRight,
+}
+
+impl fmt::Display for Direction {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match self {
+            Direction::Up => "up",
+            Direction::Down => "down",
+            Direction::Left => "left",
+            Direction::Right => "right",
+            Direction::UpLeft => "up left",
+            Direction::UpRight => "up right",
+            Direction::DownLeft => "down left",
+            Direction::DownRight => "down right",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+/// Which way a run of moves along a path goes.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Heading {
+    Step(Direction),
+    /// To a square that isn't next to the last one, through a portal or
+    /// across the edge of a wrapping board.
+    Jump,
+}
+
+impl fmt::Display for Heading {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Heading::Step(direction) => write!(f, "{}", direction),
+            Heading::Jump => write!(f, "jump"),
+        }
+    }
+}
+
+impl Direction {
+    /// The four straight directions, clockwise from up.
+    pub const ALL: [Direction; 4] = [
+        Direction::Up,
+        Direction::Right,
+        Direction::Down,
+        Direction::Left,
+    ];
+    /// The four diagonals, the two to the right first, in the order moves
+    /// out of a square try them.
+    pub const DIAGONALS: [Direction; 4] = [
+        Direction::UpRight,
+        Direction::DownRight,
+        Direction::UpLeft,
+        Direction::DownLeft,
+    ];
+    /// Every way a single step can go, the straight directions first.
+    pub const ALL_WITH_DIAGONALS: [Direction; 8] = [
+        Direction::Up,
+        Direction::Right,
+        Direction::Down,
+        Direction::Left,
+        Direction::UpRight,
+        Direction::DownRight,
+        Direction::UpLeft,
+        Direction::DownLeft,
+    ];
+
+    /// The four straight directions in the order of `ALL`.
+    pub fn all() -> impl Iterator<Item = Direction> {
+        Direction::ALL.iter().copied()
+    }
+    /// How far a single step this way moves along each axis, with up being
+    /// towards row zero.
+    pub fn delta(self) -> (i32, i32) {
+        match self {
+            Direction::Up => (0, -1),
+            Direction::Down => (0, 1),
+            Direction::Left => (-1, 0),
+            Direction::Right => (1, 0),
+            Direction::UpLeft => (-1, -1),
+            Direction::UpRight => (1, -1),
+            Direction::DownLeft => (-1, 1),
+            Direction::DownRight => (1, 1),
+        }
+    }
+}
+
+impl Heading {
+    fn between(from: (i32, i32), to: (i32, i32)) -> Heading {
+        let delta = (to.0 - from.0, to.1 - from.1);
+        Direction::ALL_WITH_DIAGONALS
+            .iter()
+            .copied()
+            .find(|direction| direction.delta() == delta)
+            .map_or(Heading::Jump, Heading::Step)
+    }
+}
+
+/// Turns a path into runs of moves in the same direction, so that going
+/// right three squares and then down two comes out as
+/// `[(Step(Right), 3), (Step(Down), 2)]`.
+pub fn path_to_directions(path: &[(i32, i32)]) -> Vec<(Heading, u32)> {
+    let mut runs: Vec<(Heading, u32)> = vec![];
+    for step in path.windows(2) {
+        let heading = Heading::between(step[0], step[1]);
+        match runs.last_mut() {
+            Some((last, count)) if *last == heading => *count += 1,
+            _ => runs.push((heading, 1)),
+        }
+    }
+    runs
+}
+
+/// Writes runs from `path_to_directions` out as text, like
+/// "right 3, down 2, right 4".
+pub fn describe_directions(runs: &[(Heading, u32)]) -> String {
+    runs.iter()
+        .map(|(heading, count)| format!("{} {}", heading, count))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+impl Maze {
+    /// Checks that `path` runs from the start square to an end square, each
+    /// square a single legal move from the one before under the board's
+    /// current movement rules.
+    pub fn check_path(&self, path: &[(i32, i32)]) -> Result<(), PathError> {
+        let start = self.find_square(SquareKind::StartSquare);
+        match path.first() {
+            None => return Err(PathError::Empty),
+            Some(&first) if Some(first) != start => return Err(PathError::WrongStart),
+            Some(_) => {}
+        }
+
+        for (index, &(x, y)) in path.iter().enumerate() {
+            match self.get(x, y) {
+                None => return Err(PathError::OutOfBounds { index }),
+                Some(SquareKind::Obstacle) => return Err(PathError::Obstacle { index }),
+                Some(_) => {}
+            }
+        }
+
+        // moves never lead onto the start, but the wall follower can step
+        // back over it, so those steps are checked the other way around
+        let reaches = |from: (i32, i32), to: (i32, i32)| {
+            self.get_possible_moves(from)
+                .iter()
+                .any(|m| (m.0, m.1) == to)
+        };
+        for (index, step) in path.windows(2).enumerate() {
+            let (from, to) = (step[0], step[1]);
+            let legal = if Some(to) == start {
+                reaches(to, from)
+            } else {
+                reaches(from, to)
+            };
+            if !legal {
+                return Err(PathError::IllegalMove { index: index + 1 });
+            }
+        }
+
+        let (x, y) = path[path.len() - 1];
+        if self.get(x, y) != Some(&SquareKind::EndSquare) {
+            return Err(PathError::WrongEnd);
+        }
+        Ok(())
+    }
+    pub fn is_valid_path(&self, path: &[(i32, i32)]) -> bool {
+        self.check_path(path).is_ok()
+    }
+    /// Counts the routes from the start to an end that never cross
+    /// themselves, under the board's current movement rules. A route stops at
+    /// the first end it reaches. A perfect maze has exactly one, any loop
+    /// adds more. There can be exponentially many, so the count stops once
+    /// it's past `cap`, or once it has taken too long, and says so.
+    pub fn count_paths(&self, cap: usize) -> PathCount {
+        let start = match self.find_square(SquareKind::StartSquare) {
+            Some(start) => start,
+            None => return PathCount::Exact(0),
+        };
+
+        let mut on_route = vec![vec![false; self.width as usize]; self.height as usize];
+        on_route[start.1 as usize][start.0 as usize] = true;
+        // each square along the current route with the moves out of it that
+        // are still to be tried
+        let mut stack = vec![(start, self.get_possible_moves(start))];
+        let (mut found, mut steps) = (0, 0);
+
+        while let Some((square, moves)) = stack.last_mut() {
+            let (x, y) = match moves.pop() {
+                Some(m) => (m.0, m.1),
+                None => {
+                    on_route[square.1 as usize][square.0 as usize] = false;
+                    stack.pop();
+                    continue;
+                }
+            };
+            if on_route[y as usize][x as usize] {
+                continue;
+            }
+
+            steps += 1;
+            if steps > COUNT_STEPS {
+                return PathCount::Capped(found);
+            }
+            if self.get(x, y) == Some(&SquareKind::EndSquare) {
+                found += 1;
+                if found > cap {
+                    return PathCount::Capped(found);
+                }
+                continue;
+            }
+
+            on_route[y as usize][x as usize] = true;
+            stack.push(((x, y), self.get_possible_moves((x, y))));
+        }
+
+        PathCount::Exact(found)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Maze, MazeGenerator};
+
+    #[test]
+    fn a_path_of_single_moves_is_valid() {
+        let mut maze = Maze::from_ascii("S# \n #E\n   \n").unwrap();
+        maze.allow_diagonals = true;
+        assert_eq!(maze.check_path(&[(0, 0), (0, 1), (1, 2), (2, 1)]), Ok(()));
+    }
+
+    #[test]
+    fn a_path_that_jumps_a_square_is_rejected_where_it_jumps() {
+        let mut maze = Maze::from_ascii("S# \n #E\n   \n").unwrap();
+        maze.allow_diagonals = true;
+        assert_eq!(
+            maze.check_path(&[(0, 0), (0, 2), (1, 2), (2, 1)]),
+            Err(PathError::IllegalMove { index: 1 })
+        );
+        assert_eq!(
+            maze.check_path(&[(0, 0), (1, 1), (2, 1)]),
+            Err(PathError::Obstacle { index: 1 })
+        );
+    }
+
+    #[test]
+    fn a_diagonal_between_two_walls_cuts_the_corner() {
+        let mut maze = Maze::from_ascii("S#\n#E\n").unwrap();
+        maze.allow_diagonals = true;
+        assert_eq!(
+            maze.check_path(&[(0, 0), (1, 1)]),
+            Err(PathError::IllegalMove { index: 1 })
+        );
+
+        maze.allow_corner_cutting = true;
+        assert_eq!(maze.check_path(&[(0, 0), (1, 1)]), Ok(()));
+    }
+
+    #[test]
+    fn a_zig_zag_path_becomes_runs_of_directions() {
+        use crate::Direction::*;
+        use Heading::Step;
+
+        let path = [
+            (0, 0),
+            (1, 0),
+            (2, 0),
+            (2, 1),
+            (3, 1),
+            (3, 2),
+            (3, 3),
+            (4, 4),
+            (5, 5),
+        ];
+        let runs = path_to_directions(&path);
+        assert_eq!(
+            runs,
+            vec![
+                (Step(Right), 2),
+                (Step(Down), 1),
+                (Step(Right), 1),
+                (Step(Down), 2),
+                (Step(DownRight), 2)
+            ]
+        );
+        assert_eq!(
+            describe_directions(&runs),
+            "right 2, down 1, right 1, down 2, down right 2"
+        );
+        assert!(path_to_directions(&[(0, 0)]).is_empty());
+    }
+
+    #[test]
+    fn a_perfect_maze_has_exactly_one_path() {
+        for generator in &[
+            MazeGenerator::Backtracker,
+            MazeGenerator::Prim,
+            MazeGenerator::Kruskal,
+        ] {
+            let mut maze = Maze::new(21, 21);
+            maze.move_endpoint(1, 1, SquareKind::StartSquare);
+            maze.move_endpoint(19, 19, SquareKind::EndSquare);
+            maze.generate(generator, Some(7));
+            assert_eq!(maze.count_paths(100), PathCount::Exact(1));
+        }
+    }
+
+    #[test]
+    fn counting_stops_at_the_cap() {
+        let open = Maze::from_ascii("   \n S \n  E\n").unwrap();
+        assert_eq!(open.count_paths(1), PathCount::Capped(2));
+        assert_eq!(
+            Maze::from_ascii("S#E\n").unwrap().count_paths(5),
+            PathCount::Exact(0)
+        );
+    }
+
+    #[test]
+    fn the_straight_directions_step_one_square_each_way() {
+        let deltas: Vec<_> = Direction::all().map(Direction::delta).collect();
+        assert_eq!(deltas, vec![(0, -1), (1, 0), (0, 1), (-1, 0)]);
+    }
+
+    #[test]
+    fn every_step_has_its_opposite() {
+        for direction in Direction::ALL_WITH_DIAGONALS {
+            let (dx, dy) = direction.delta();
+            assert!(dx.abs() <= 1 && dy.abs() <= 1 && (dx, dy) != (0, 0));
+            assert!(
+                Direction::ALL_WITH_DIAGONALS
+                    .iter()
+                    .any(|other| other.delta() == (-dx, -dy)),
+                "{:?} can't be undone",
+                direction
+            );
+        }
+    }
+
+    #[test]
+    fn a_step_to_a_square_further_off_is_a_jump() {
+        let runs = path_to_directions(&[(0, 0), (1, 0), (4, 0), (5, 0)]);
+        let right = Heading::Step(Direction::Right);
+        assert_eq!(runs, vec![(right, 1), (Heading::Jump, 1), (right, 1)]);
+        assert_eq!(describe_directions(&runs), "right 1, jump 1, right 1");
+    }
+}
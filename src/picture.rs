@@ -0,0 +1,223 @@
+use crate::validate::check_dimensions;
+use crate::{DimensionError, Maze, SquareKind};
+use image::{ImageError, Rgb, RgbImage};
+use std::convert::TryFrom;
+use std::fmt;
+use std::path::Path;
+
+/// The colour a picture marks the start with.
+pub const START_MARKER: Rgb<u8> = Rgb([0, 255, 0]);
+/// The colour a picture marks the end with.
+pub const END_MARKER: Rgb<u8> = Rgb([255, 0, 255]);
+
+// how far each channel of a pixel can stray from a marker's colour and still
+// count as it, so markers survive being saved with anti-aliasing or lossy
+// compression
+const MARKER_TOLERANCE: u8 = 64;
+
+// what stops a picture from being read as a board
+#[derive(Debug)]
+pub enum PictureError {
+    Image(ImageError),
+    // a picture with no pixels has nothing to scale down
+    Empty,
+    Dimensions(DimensionError),
+}
+
+impl fmt::Display for PictureError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            PictureError::Image(err) => write!(f, "couldn't read picture: {}", err),
+            PictureError::Empty => write!(f, "picture has no pixels"),
+            PictureError::Dimensions(err) => write!(f, "{}", err),
+        }
+    }
+}
+
+impl std::error::Error for PictureError {}
+
+impl From<ImageError> for PictureError {
+    fn from(err: ImageError) -> Self {
+        PictureError::Image(err)
+    }
+}
+
+impl From<DimensionError> for PictureError {
+    fn from(err: DimensionError) -> Self {
+        PictureError::Dimensions(err)
+    }
+}
+
+impl Maze {
+    /// Reads the colour or grayscale picture at `path` as a board, see
+    /// `from_picture`.
+    pub fn from_image(
+        path: &Path,
+        width: u32,
+        height: u32,
+        threshold: u8,
+    ) -> Result<Maze, PictureError> {
+        let picture = image::open(path)?.to_rgb8();
+        // a side too long for an i32 is still too long once saturated, so
+        // `check_dimensions` turns it down
+        let side = |n: u32| i32::try_from(n).unwrap_or(i32::MAX);
+        Maze::from_picture(&picture, side(width), side(height), threshold)
+    }
+    /// Scales `picture` down to a `width` by `height` board, each square
+    /// covering an even share of the pixels. A square whose pixels are darker
+    /// than `threshold` on average is a wall, and any other is open. The
+    /// first square, row by row, holding a `START_MARKER` pixel is the start,
+    /// and likewise for `END_MARKER` and the end. Without a marker the
+    /// endpoint goes where `Maze::new` puts it, or where it puts the other
+    /// endpoint if the marked one is already there.
+    pub fn from_picture(
+        picture: &RgbImage,
+        width: i32,
+        height: i32,
+        threshold: u8,
+    ) -> Result<Maze, PictureError> {
+        check_dimensions(height, width)?;
+        if picture.width() == 0 || picture.height() == 0 {
+            return Err(PictureError::Empty);
+        }
+
+        let mut maze = Maze::new(height, width);
+        let default_start = maze.find_square(SquareKind::StartSquare);
+        let default_end = maze.find_square(SquareKind::EndSquare);
+
+        let (mut start, mut end) = (None, None);
+        for y in 0..height {
+            for x in 0..width {
+                let pixels = cell_pixels(picture, (x, y), (width, height));
+                if start.is_none() && pixels.iter().any(|p| is_marker(p, &START_MARKER)) {
+                    start = Some((x, y));
+                } else if end.is_none() && pixels.iter().any(|p| is_marker(p, &END_MARKER)) {
+                    end = Some((x, y));
+                }
+
+                let kind = if average_luma(&pixels) < threshold as u32 {
+                    SquareKind::Obstacle
+                } else {
+                    SquareKind::Init
+                };
+                maze.set(x, y, kind);
+            }
+        }
+
+        // an endpoint without a marker makes way for a marked one on its
+        // square by taking the other's
+        let end = end.or(if start == default_end {
+            default_start
+        } else {
+            default_end
+        });
+        let start = start.or(if end == default_start {
+            default_end
+        } else {
+            default_start
+        });
+        if let Some((x, y)) = end {
+            maze.set(x, y, SquareKind::EndSquare);
+        }
+        if let Some((x, y)) = start {
+            maze.set(x, y, SquareKind::StartSquare);
+        }
+        Ok(maze)
+    }
+}
+
+// the pixels the square at `square` covers on a board of `size` squares,
+// always at least one even when the board has more squares than the picture
+// has pixels
+fn cell_pixels(picture: &RgbImage, square: (i32, i32), size: (i32, i32)) -> Vec<Rgb<u8>> {
+    let span = |index: i32, squares: i32, pixels: u32| {
+        let (index, squares, pixels) = (index as u64, squares as u64, pixels as u64);
+        let from = index * pixels / squares;
+        let to = ((index + 1) * pixels / squares).max(from + 1);
+        from as u32..to as u32
+    };
+
+    let mut result = vec![];
+    for py in span(square.1, size.1, picture.height()) {
+        for px in span(square.0, size.0, picture.width()) {
+            result.push(*picture.get_pixel(px, py));
+        }
+    }
+    result
+}
+
+fn is_marker(pixel: &Rgb<u8>, marker: &Rgb<u8>) -> bool {
+    pixel
+        .0
+        .iter()
+        .zip(marker.0.iter())
+        .all(|(a, b)| a.abs_diff(*b) <= MARKER_TOLERANCE)
+}
+
+// how light the pixels are on average, weighted the way the eye sees each
+// channel, from 0 to 255
+fn average_luma(pixels: &[Rgb<u8>]) -> u32 {
+    let total: u64 = pixels
+        .iter()
+        .map(|Rgb([r, g, b])| 299 * *r as u64 + 587 * *g as u64 + 114 * *b as u64)
+        .sum();
+    (total / (1000 * pixels.len().max(1) as u64)) as u32
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn dark_pixels_become_walls_and_markers_the_endpoints() {
+        let mut picture = RgbImage::from_fn(6, 3, |x, _| {
+            if x == 1 {
+                Rgb([0, 0, 0])
+            } else {
+                Rgb([255, 255, 255])
+            }
+        });
+        // a little off the marker colors, as a lossy save would leave them
+        picture.put_pixel(0, 2, Rgb([20, 230, 30]));
+        picture.put_pixel(5, 0, Rgb([250, 10, 240]));
+
+        let maze = Maze::from_picture(&picture, 6, 3, 128).unwrap();
+        assert_eq!(maze.to_ascii(), " #   E\n #    \nS#    \n");
+    }
+
+    #[test]
+    fn a_start_marked_on_the_default_end_moves_the_end() {
+        let mut picture = RgbImage::from_fn(3, 3, |_, _| Rgb([255, 255, 255]));
+        let fresh = Maze::new(3, 3);
+        let default_start = fresh.find_square(SquareKind::StartSquare).unwrap();
+        let default_end = fresh.find_square(SquareKind::EndSquare).unwrap();
+        picture.put_pixel(default_end.0 as u32, default_end.1 as u32, START_MARKER);
+
+        let maze = Maze::from_picture(&picture, 3, 3, 128).unwrap();
+        assert_eq!(maze.find_square(SquareKind::StartSquare), Some(default_end));
+        assert_eq!(maze.find_square(SquareKind::EndSquare), Some(default_start));
+        assert!(maze.validate().is_ok());
+    }
+
+    #[test]
+    fn an_end_marked_on_the_default_start_moves_the_start() {
+        let mut picture = RgbImage::from_fn(3, 3, |_, _| Rgb([255, 255, 255]));
+        let fresh = Maze::new(3, 3);
+        let default_start = fresh.find_square(SquareKind::StartSquare).unwrap();
+        let default_end = fresh.find_square(SquareKind::EndSquare).unwrap();
+        picture.put_pixel(default_start.0 as u32, default_start.1 as u32, END_MARKER);
+
+        let maze = Maze::from_picture(&picture, 3, 3, 128).unwrap();
+        assert_eq!(maze.find_square(SquareKind::EndSquare), Some(default_start));
+        assert_eq!(maze.find_square(SquareKind::StartSquare), Some(default_end));
+    }
+
+    #[test]
+    fn a_png_loads_through_from_image() {
+        // the fixture is the picture above saved as a 12 by 6 png, two pixels
+        // a square
+        let path = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/picture.png");
+        let maze = Maze::from_image(&path, 6, 3, 128).unwrap();
+        assert_eq!(maze.to_ascii(), " #   E\n #    \nS#    \n");
+    }
+}
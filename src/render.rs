@@ -0,0 +1,51 @@
+use image::{ImageResult, Rgb, RgbImage};
+use std::path::Path;
+
+use pollen::Maze;
+
+use crate::palette::Palette;
+use crate::square_color;
+
+pub trait Render {
+    fn render_image(&self, palette: &Palette, cell_px: u32) -> RgbImage;
+    fn render_png(&self, path: &Path, palette: &Palette, cell_px: u32) -> ImageResult<()>;
+}
+
+impl Render for Maze {
+    fn render_image(&self, palette: &Palette, cell_px: u32) -> RgbImage {
+        let cell_px = cell_px.max(1);
+        let (width, height) = (self.width as u32 * cell_px, self.height as u32 * cell_px);
+
+        let (r, g, b, _) = palette.grid.as_rgba8();
+        let grid = Rgb([r, g, b]);
+
+        RgbImage::from_fn(width, height, |px, py| {
+            // a 1px border around every square, like the painter's stroke
+            if px % cell_px == 0 || py % cell_px == 0 || px == width - 1 || py == height - 1 {
+                return grid;
+            }
+
+            let (x, y) = ((px / cell_px) as i32, (py / cell_px) as i32);
+            // the image shows everything the search did, whatever's hidden
+            // in the window
+            let color = square_color(palette, self, x, y, false);
+            let (r, g, b, _) = color.as_rgba8();
+            Rgb([r, g, b])
+        })
+    }
+    fn render_png(&self, path: &Path, palette: &Palette, cell_px: u32) -> ImageResult<()> {
+        self.render_image(palette, cell_px).save(path)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn image_is_cell_px_per_square() {
+        let maze = Maze::new(4, 7);
+        let image = maze.render_image(&Palette::light(), 6);
+        assert_eq!(image.dimensions(), (7 * 6, 4 * 6));
+    }
+}
@@ -0,0 +1,48 @@
+use crate::{Algorithm, Maze};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::io;
+use std::path::Path;
+use std::time::Duration;
+
+// what a solve found and what it took, for reading by other programs
+#[derive(Serialize, Deserialize, PartialEq, Debug)]
+pub struct SolveReport {
+    pub algorithm: Algorithm,
+    // whether an end was reached, or left out when the search didn't get far
+    // enough to tell
+    pub solvable: Option<bool>,
+    pub path_length: usize,
+    pub path: Vec<(i32, i32)>,
+    pub explored: usize,
+    pub elapsed_ms: f64,
+}
+
+impl SolveReport {
+    /// Describes the search last run over `maze` by `algorithm`, which took
+    /// `elapsed`.
+    pub fn new(maze: &Maze, algorithm: Algorithm, elapsed: Duration) -> SolveReport {
+        let solvable = if maze.solved {
+            Some(true)
+        } else if maze.unsolvable {
+            Some(false)
+        } else {
+            None
+        };
+
+        SolveReport {
+            algorithm,
+            solvable,
+            path_length: maze.last_path_len,
+            path: maze.path.clone(),
+            explored: maze.last_explored,
+            elapsed_ms: elapsed.as_secs_f64() * 1000.0,
+        }
+    }
+    pub fn to_json(&self) -> serde_json::Result<String> {
+        serde_json::to_string_pretty(self)
+    }
+    pub fn save(&self, path: &Path) -> io::Result<()> {
+        fs::write(path, self.to_json()?)
+    }
+}
@@ -0,0 +1,150 @@
+use pollen::{Algorithm, DEFAULT_HEIGHT, DEFAULT_WIDTH};
+use serde::{Deserialize, Serialize};
+use std::env;
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use crate::palette::{GridStyle, Palette};
+use crate::{Metadata, State, DEFAULT_SPEED_MS, MAX_SPEED_MS, MAX_STEPS_PER_FRAME, MIN_SPEED_MS};
+use crate::{MAX_GRID_WIDTH, MIN_GRID_WIDTH};
+
+// the choices worth keeping between runs. Anything missing from the file,
+// say from an older version, falls back to its default.
+#[derive(Serialize, Deserialize)]
+#[serde(default)]
+pub struct Settings {
+    pub width: i32,
+    pub height: i32,
+    pub algorithm: Algorithm,
+    pub dark_theme: bool,
+    pub speed_ms: f64,
+    pub steps_per_frame: u32,
+    pub grid: GridStyle,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Settings {
+            width: DEFAULT_WIDTH,
+            height: DEFAULT_HEIGHT,
+            algorithm: Algorithm::Dfs,
+            dark_theme: false,
+            speed_ms: DEFAULT_SPEED_MS,
+            steps_per_frame: 1,
+            grid: GridStyle::default(),
+        }
+    }
+}
+
+impl Settings {
+    pub fn from_state(data: &State) -> Settings {
+        Settings {
+            width: data.maze.width,
+            height: data.maze.height,
+            algorithm: (*data.algorithm).clone(),
+            dark_theme: data.palette.dark,
+            speed_ms: data.speed_ms,
+            steps_per_frame: data.steps_per_frame,
+            grid: data.grid.clone(),
+        }
+    }
+    pub fn apply(&self, data: &mut State) {
+        data.resize(self.height, self.width);
+        data.algorithm = Arc::new(self.algorithm.clone());
+        data.palette = Arc::new(if self.dark_theme {
+            Palette::dark()
+        } else {
+            Palette::light()
+        });
+        data.speed_ms = self.speed_ms.clamp(MIN_SPEED_MS, MAX_SPEED_MS);
+        data.steps_per_frame = self.steps_per_frame.clamp(1, MAX_STEPS_PER_FRAME);
+        data.grid = GridStyle {
+            show: self.grid.show,
+            width: self.grid.width.clamp(MIN_GRID_WIDTH, MAX_GRID_WIDTH),
+        };
+    }
+    // a missing file is a first run, anything unreadable is reported and
+    // otherwise ignored so a bad file never stops the window from opening
+    pub fn load() -> Settings {
+        let path = match settings_file() {
+            Some(path) => path,
+            None => return Settings::default(),
+        };
+
+        match fs::read_to_string(&path) {
+            Ok(json) => serde_json::from_str(&json).unwrap_or_else(|err| {
+                eprintln!("warning: ignoring {}: {}", path.display(), err);
+                Settings::default()
+            }),
+            Err(err) if err.kind() == io::ErrorKind::NotFound => Settings::default(),
+            Err(err) => {
+                eprintln!("warning: ignoring {}: {}", path.display(), err);
+                Settings::default()
+            }
+        }
+    }
+    pub fn save(&self) -> io::Result<()> {
+        let path = settings_file()
+            .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, "no config directory"))?;
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        fs::write(path, serde_json::to_string_pretty(self)?)
+    }
+}
+
+// where each platform keeps per-user configuration
+fn settings_file() -> Option<PathBuf> {
+    let dir = if cfg!(target_os = "windows") {
+        env::var_os("APPDATA").map(PathBuf::from)
+    } else if cfg!(target_os = "macos") {
+        env::var_os("HOME").map(|home| PathBuf::from(home).join("Library/Application Support"))
+    } else {
+        env::var_os("XDG_CONFIG_HOME")
+            .map(PathBuf::from)
+            .or_else(|| env::var_os("HOME").map(|home| PathBuf::from(home).join(".config")))
+    };
+    dir.map(|dir| dir.join("pollen").join("settings.json"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn settings_round_trip_through_json() {
+        let settings = Settings {
+            width: 42,
+            height: 17,
+            algorithm: Algorithm::AStar,
+            dark_theme: true,
+            speed_ms: 250.0,
+            steps_per_frame: 8,
+            grid: GridStyle {
+                show: false,
+                width: 2.5,
+            },
+        };
+        let json = serde_json::to_string_pretty(&settings).unwrap();
+        let read: Settings = serde_json::from_str(&json).unwrap();
+
+        assert_eq!((read.width, read.height), (42, 17));
+        assert!(read.algorithm == Algorithm::AStar);
+        assert!(read.dark_theme);
+        assert_eq!(read.speed_ms, 250.0);
+        assert_eq!(read.steps_per_frame, 8);
+        assert!(read.grid == settings.grid);
+    }
+
+    #[test]
+    fn missing_settings_fall_back_to_their_defaults() {
+        let read: Settings = serde_json::from_str(r#"{ "width": 30 }"#).unwrap();
+        assert_eq!((read.width, read.height), (30, DEFAULT_HEIGHT));
+        assert!(read.algorithm == Algorithm::Dfs);
+        assert!(read.grid == GridStyle::default());
+
+        assert!(serde_json::from_str::<Settings>("not json").is_err());
+    }
+}
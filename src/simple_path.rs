@@ -0,0 +1,105 @@
+use crate::{Maze, SquareKind};
+
+// a square on the route along with the moves out of it still to be tried
+type Waypoint = ((i32, i32), Vec<(i32, i32)>);
+
+// a depth first search that only keeps off the squares on the route it's
+// following. Backing out of a dead end unmarks it, so it can be tried again
+// after being reached a different way.
+#[derive(Clone)]
+pub(crate) struct SimplePath {
+    // the route from the start
+    route: Vec<Waypoint>,
+    on_route: Vec<Vec<bool>>,
+}
+
+impl Maze {
+    pub(crate) fn begin_simple_path(&mut self, start: (i32, i32)) {
+        let mut on_route = vec![vec![false; self.width as usize]; self.height as usize];
+        on_route[start.1 as usize][start.0 as usize] = true;
+        self.simple_path = Some(SimplePath {
+            route: vec![(start, self.untried_moves(start))],
+            on_route,
+        });
+    }
+    pub(crate) fn step_simple_path(&mut self) -> bool {
+        let mut search = match self.simple_path.take() {
+            Some(search) => search,
+            None => return false,
+        };
+
+        // moves back onto the route are skipped without using up a step
+        loop {
+            let (square, next) = match search.route.last_mut() {
+                Some((square, untried)) => (*square, untried.pop()),
+                None => {
+                    // backed all the way out of the start
+                    self.unsolvable = true;
+                    return false;
+                }
+            };
+
+            match next {
+                Some((x, y)) if search.on_route[y as usize][x as usize] => continue,
+                Some((x, y)) => {
+                    if self.get(x, y) == Some(&SquareKind::EndSquare) {
+                        let mut path: Vec<(i32, i32)> =
+                            search.route.iter().map(|(square, _)| *square).collect();
+                        path.push((x, y));
+                        self.mark_solution(&path);
+                        return false;
+                    }
+
+                    self.record_expansion(x, y);
+                    self.dist[y as usize][x as usize] = Some(search.route.len() as f64);
+                    self.mark_square(x, y, SquareKind::PossiblePath);
+                    search.on_route[y as usize][x as usize] = true;
+                    search.route.push(((x, y), self.untried_moves((x, y))));
+                }
+                None => {
+                    let (x, y) = square;
+                    search.route.pop();
+                    search.on_route[y as usize][x as usize] = false;
+                    if self.get(x, y) == Some(&SquareKind::PossiblePath) {
+                        self.set(x, y, SquareKind::Init);
+                        self.dist[y as usize][x as usize] = None;
+                    }
+                }
+            }
+
+            self.simple_path = Some(search);
+            return true;
+        }
+    }
+    fn untried_moves(&self, square: (i32, i32)) -> Vec<(i32, i32)> {
+        self.get_possible_moves(square)
+            .iter()
+            .map(|m| (m.0, m.1))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Maze, RevisitPolicy, SquareKind};
+
+    #[test]
+    fn keeping_off_only_the_route_revisits_squares_on_a_board_with_loops() {
+        let text = "S # \n    \n    \n  #E\n";
+        let mut global = Maze::from_ascii(text).unwrap();
+        let global_path = global.traverse();
+        let mut current = Maze::from_ascii(text).unwrap();
+        current.revisit_policy = RevisitPolicy::CurrentPathOnly;
+        let current_path = current.traverse();
+
+        // both take the first route in move order, but only keeping off the
+        // route means trying the squares it backed out of again
+        assert_eq!(current_path, global_path);
+        assert!(current.is_valid_path(&current_path));
+        assert_eq!((global.last_explored, current.last_explored), (12, 16));
+
+        // and the dead ends it backed out of are left unmarked
+        assert!(!global.find_squares(SquareKind::PossiblePath).is_empty());
+        assert!(current.find_squares(SquareKind::PossiblePath).is_empty());
+    }
+}
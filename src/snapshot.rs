@@ -0,0 +1,136 @@
+use crate::{Algorithm, Maze, ParseError};
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+// why a solved board didn't come out the way it was expected to
+#[derive(Debug)]
+pub enum SnapshotError {
+    Io(io::Error),
+    Parse(ParseError),
+    // the first line, counted from one, where the solved board and the
+    // expected one differ. A board that runs out of lines first has an empty
+    // line there.
+    Mismatch {
+        line: usize,
+        expected: String,
+        actual: String,
+    },
+}
+
+impl fmt::Display for SnapshotError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            SnapshotError::Io(err) => write!(f, "couldn't read snapshot: {}", err),
+            SnapshotError::Parse(err) => write!(f, "couldn't parse maze: {}", err),
+            SnapshotError::Mismatch {
+                line,
+                expected,
+                actual,
+            } => write!(
+                f,
+                "line {} differs, expected {:?} but got {:?}",
+                line, expected, actual
+            ),
+        }
+    }
+}
+
+impl std::error::Error for SnapshotError {}
+
+impl From<io::Error> for SnapshotError {
+    fn from(err: io::Error) -> Self {
+        SnapshotError::Io(err)
+    }
+}
+
+impl From<ParseError> for SnapshotError {
+    fn from(err: ParseError) -> Self {
+        SnapshotError::Parse(err)
+    }
+}
+
+/// Solves the ASCII maze in `maze_path` with `algorithm` and checks the
+/// solved board, as `to_ascii` writes it, matches the one in `expected_path`
+/// exactly. Every solver is deterministic on a given board, depth first
+/// search included as long as its `neighbour_order` is left alone, so a
+/// mismatch means the search or its marking has changed.
+pub fn solve_matches(
+    maze_path: &Path,
+    algorithm: Algorithm,
+    expected_path: &Path,
+) -> Result<(), SnapshotError> {
+    let mut maze = Maze::from_ascii(&fs::read_to_string(maze_path)?)?;
+    maze.run(algorithm);
+    compare_snapshot(&maze.to_ascii(), &fs::read_to_string(expected_path)?)
+}
+
+/// `solve_matches` for a test, panicking with what differed when the solved
+/// board doesn't match.
+pub fn assert_solve_matches(maze_path: &Path, algorithm: Algorithm, expected_path: &Path) {
+    if let Err(err) = solve_matches(maze_path, algorithm.clone(), expected_path) {
+        panic!(
+            "{} solved with {:?} doesn't match {}: {}",
+            maze_path.display(),
+            algorithm,
+            expected_path.display(),
+            err
+        );
+    }
+}
+
+/// Compares two boards line by line, ignoring a trailing newline or
+/// carriage returns either may have picked up.
+pub fn compare_snapshot(actual: &str, expected: &str) -> Result<(), SnapshotError> {
+    let actual: Vec<&str> = actual.lines().collect();
+    let expected: Vec<&str> = expected.lines().collect();
+
+    for index in 0..actual.len().max(expected.len()) {
+        let (a, e) = (
+            actual.get(index).copied().unwrap_or(""),
+            expected.get(index).copied().unwrap_or(""),
+        );
+        if a != e || index >= actual.len() || index >= expected.len() {
+            return Err(SnapshotError::Mismatch {
+                line: index + 1,
+                expected: e.to_string(),
+                actual: a.to_string(),
+            });
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    #[test]
+    fn every_fixture_solves_to_its_snapshot() {
+        let fixtures = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("benches/fixtures");
+        let algorithms = [
+            ("dfs", Algorithm::Dfs),
+            ("bfs", Algorithm::Bfs),
+            ("astar", Algorithm::AStar),
+        ];
+        for name in &["open", "rooms", "serpentine"] {
+            for (suffix, algorithm) in &algorithms {
+                assert_solve_matches(
+                    &fixtures.join(format!("{}.txt", name)),
+                    algorithm.clone(),
+                    &fixtures.join(format!("solved/{}.{}.txt", name, suffix)),
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn the_first_differing_line_is_reported() {
+        let err = compare_snapshot("ab\ncd\n", "ab\nce\n").unwrap_err();
+        assert!(matches!(err, SnapshotError::Mismatch { line: 2, .. }));
+        assert!(compare_snapshot("ab\n", "ab\ncd\n").is_err());
+        assert!(compare_snapshot("ab\r\ncd", "ab\ncd\n").is_ok());
+    }
+}
@@ -0,0 +1,113 @@
+use crate::validate::check_dimensions;
+use crate::{DimensionError, Maze, SquareKind};
+use std::fmt;
+
+// what stops a board from being built out of lists of squares
+#[derive(Debug, PartialEq)]
+pub enum SpecError {
+    Dimensions(DimensionError),
+    OutOfBounds { square: (i32, i32) },
+    // the start and end are the same square, or a wall is on one of them
+    Overlap { square: (i32, i32) },
+}
+
+impl fmt::Display for SpecError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            SpecError::Dimensions(err) => write!(f, "{}", err),
+            SpecError::OutOfBounds { square } => {
+                write!(f, "({}, {}) is off the board", square.0, square.1)
+            }
+            SpecError::Overlap { square } => {
+                write!(f, "({}, {}) is given more than once", square.0, square.1)
+            }
+        }
+    }
+}
+
+impl std::error::Error for SpecError {}
+
+impl From<DimensionError> for SpecError {
+    fn from(err: DimensionError) -> Self {
+        SpecError::Dimensions(err)
+    }
+}
+
+impl Maze {
+    /// Builds a `width` by `height` board with its start, end and walls
+    /// where they're listed and every other square open, for setting up a
+    /// board in code without drawing it in ASCII first. A wall can be listed
+    /// more than once.
+    pub fn from_spec(
+        width: i32,
+        height: i32,
+        start: (i32, i32),
+        end: (i32, i32),
+        obstacles: &[(i32, i32)],
+    ) -> Result<Maze, SpecError> {
+        check_dimensions(height, width)?;
+        let mut maze = Maze::new(height, width);
+
+        let endpoints = [start, end];
+        let mut squares = endpoints.iter().chain(obstacles.iter());
+        if let Some(&square) = squares.find(|(x, y)| maze.get(*x, *y).is_none()) {
+            return Err(SpecError::OutOfBounds { square });
+        }
+        if start == end {
+            return Err(SpecError::Overlap { square: end });
+        }
+        if let Some(&square) = obstacles
+            .iter()
+            .find(|&&square| square == start || square == end)
+        {
+            return Err(SpecError::Overlap { square });
+        }
+
+        // the endpoints `new` puts down make way for the listed ones
+        for square in maze.squares.iter_mut().flatten() {
+            *square = SquareKind::Init;
+        }
+        for &(x, y) in obstacles {
+            maze.set(x, y, SquareKind::Obstacle);
+        }
+        maze.set(start.0, start.1, SquareKind::StartSquare);
+        maze.set(end.0, end.1, SquareKind::EndSquare);
+        Ok(maze)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Algorithm;
+
+    #[test]
+    fn a_listed_board_builds_and_solves() {
+        let mut maze = Maze::from_spec(3, 3, (0, 0), (2, 2), &[(1, 0), (1, 1), (1, 1)]).unwrap();
+        assert_eq!(maze.to_ascii(), "S# \n # \n  E\n");
+
+        let path = maze.run(Algorithm::Bfs);
+        assert!(maze.solved);
+        assert_eq!(path, vec![(0, 0), (0, 1), (0, 2), (1, 2), (2, 2)]);
+    }
+
+    #[test]
+    fn squares_off_the_board_or_on_each_other_are_refused() {
+        assert_eq!(
+            Maze::from_spec(3, 3, (0, 0), (3, 2), &[]).err(),
+            Some(SpecError::OutOfBounds { square: (3, 2) })
+        );
+        assert_eq!(
+            Maze::from_spec(3, 3, (0, 0), (0, 0), &[]).err(),
+            Some(SpecError::Overlap { square: (0, 0) })
+        );
+        assert_eq!(
+            Maze::from_spec(3, 3, (0, 0), (2, 2), &[(2, 2)]).err(),
+            Some(SpecError::Overlap { square: (2, 2) })
+        );
+        assert!(matches!(
+            Maze::from_spec(1, 3, (0, 0), (0, 2), &[]),
+            Err(SpecError::Dimensions(_))
+        ));
+    }
+}
@@ -0,0 +1,145 @@
+use crate::{without_search, Maze, SquareKind, MAX_LOADED_DIMENSION};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+// the parts of a board worth keeping between runs; exploration and solution
+// markings are dropped so a saved maze always loads unsolved
+#[derive(Serialize, Deserialize, PartialEq, Debug)]
+pub struct SavedMaze {
+    width: i32,
+    height: i32,
+    squares: Vec<Vec<SquareKind>>,
+    cost: Vec<Vec<u32>>,
+}
+
+#[derive(Debug)]
+pub enum MazeFileError {
+    Io(io::Error),
+    Json(serde_json::Error),
+    Dimensions,
+    // more than `MAX_LOADED_DIMENSION` squares a side
+    TooLarge,
+    Endpoints,
+}
+
+impl fmt::Display for MazeFileError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            MazeFileError::Io(err) => write!(f, "couldn't access maze file: {}", err),
+            MazeFileError::Json(err) => write!(f, "couldn't parse maze file: {}", err),
+            MazeFileError::Dimensions => write!(f, "maze dimensions don't match its squares"),
+            MazeFileError::TooLarge => write!(
+                f,
+                "maze is more than {} squares a side",
+                MAX_LOADED_DIMENSION
+            ),
+            MazeFileError::Endpoints => write!(f, "maze needs one start and at least one end"),
+        }
+    }
+}
+
+impl From<io::Error> for MazeFileError {
+    fn from(err: io::Error) -> Self {
+        MazeFileError::Io(err)
+    }
+}
+
+impl From<serde_json::Error> for MazeFileError {
+    fn from(err: serde_json::Error) -> Self {
+        MazeFileError::Json(err)
+    }
+}
+
+impl Maze {
+    pub fn to_saved(&self) -> SavedMaze {
+        let squares = self
+            .squares
+            .iter()
+            .map(|row| row.iter().map(without_search).collect())
+            .collect();
+
+        SavedMaze {
+            width: self.width,
+            height: self.height,
+            squares,
+            cost: self.cost.clone(),
+        }
+    }
+    pub fn load_saved(&mut self, saved: SavedMaze) -> Result<(), MazeFileError> {
+        if saved.width > MAX_LOADED_DIMENSION || saved.height > MAX_LOADED_DIMENSION {
+            return Err(MazeFileError::TooLarge);
+        }
+        if saved.width <= 0
+            || saved.height <= 0
+            || !has_shape(&saved.squares, saved.width, saved.height)
+            || !has_shape(&saved.cost, saved.width, saved.height)
+        {
+            return Err(MazeFileError::Dimensions);
+        }
+
+        let count = |kind: SquareKind| {
+            saved
+                .squares
+                .iter()
+                .flatten()
+                .filter(|square_kind| **square_kind == kind)
+                .count()
+        };
+        if count(SquareKind::StartSquare) != 1 || count(SquareKind::EndSquare) == 0 {
+            return Err(MazeFileError::Endpoints);
+        }
+
+        self.width = saved.width;
+        self.height = saved.height;
+        self.squares = saved.squares;
+        self.cost = saved.cost;
+        self.locked = vec![vec![false; self.width as usize]; self.height as usize];
+        self.reset_search();
+        Ok(())
+    }
+    pub fn save_to_file(&self, path: &Path) -> Result<(), MazeFileError> {
+        let json = serde_json::to_string_pretty(&self.to_saved())?;
+        fs::write(path, json)?;
+        Ok(())
+    }
+    pub fn load_maze(&mut self, path: &Path) -> Result<(), MazeFileError> {
+        let json = fs::read_to_string(path)?;
+        self.load_saved(serde_json::from_str(&json)?)
+    }
+    pub fn load_from_file(path: &Path) -> Result<Maze, MazeFileError> {
+        let mut maze = Maze::default();
+        maze.load_maze(path)?;
+        Ok(maze)
+    }
+}
+
+fn has_shape<T>(grid: &[Vec<T>], width: i32, height: i32) -> bool {
+    grid.len() == height as usize && grid.iter().all(|row| row.len() == width as usize)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn saved_maze_loads_back_the_same() {
+        let mut maze = Maze::from_ascii("S # 0\n  #  \n0   E\n").unwrap();
+        maze.set_square(1, 1, SquareKind::Init, 5);
+        maze.traverse_bfs();
+
+        let path = std::env::temp_dir().join(format!("pollen-save-{}.json", std::process::id()));
+        maze.save_to_file(&path).unwrap();
+        let loaded = Maze::load_from_file(&path);
+        fs::remove_file(&path).unwrap();
+
+        // the solution isn't saved, only the board it was found on
+        let loaded = loaded.unwrap();
+        assert_eq!(loaded.to_saved(), maze.to_saved());
+        assert_eq!((loaded.width, loaded.height), (5, 3));
+        assert!(!loaded.solved);
+        assert_eq!(loaded.get(0, 2), Some(&SquareKind::Portal(0)));
+    }
+}
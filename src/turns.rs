@@ -0,0 +1,207 @@
+use std::cmp::Ordering;
+use std::collections::{BinaryHeap, HashMap, HashSet};
+
+use crate::{reconstruct_path, Algorithm, Maze, Move, SquareKind};
+
+// the way the search was moving when it reached a square, which the start
+// has none of
+type Heading = Option<(i32, i32)>;
+
+// a square along with the way it was reached, since turning out of it costs
+// more from some directions than others
+type Approach = ((i32, i32), Heading);
+
+// an approach waiting in the heap along with the cost of reaching it,
+// ordered so that the lowest priority is popped first
+#[derive(Clone)]
+struct Pending {
+    priority: f64,
+    cost: f64,
+    approach: Approach,
+    from: Approach,
+}
+
+impl PartialEq for Pending {
+    fn eq(&self, other: &Self) -> bool {
+        self.priority == other.priority
+    }
+}
+
+impl Eq for Pending {}
+
+impl PartialOrd for Pending {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Pending {
+    fn cmp(&self, other: &Self) -> Ordering {
+        other.priority.total_cmp(&self.priority)
+    }
+}
+
+// A* or Dijkstra charging `turn_cost` whenever the route changes direction.
+// A square can be worth expanding once for each way into it, so everything
+// is kept per approach rather than per square.
+#[derive(Clone)]
+pub(crate) struct TurnSearch {
+    algorithm: Algorithm,
+    start: (i32, i32),
+    ends: Vec<(i32, i32)>,
+    portals: Vec<(i32, i32)>,
+    heap: BinaryHeap<Pending>,
+    expanded: HashSet<Approach>,
+    came_from: HashMap<Approach, Approach>,
+}
+
+impl Maze {
+    pub(crate) fn begin_turn_search(
+        &mut self,
+        algorithm: Algorithm,
+        start: (i32, i32),
+        ends: Vec<(i32, i32)>,
+    ) {
+        let mut search = TurnSearch {
+            algorithm,
+            start,
+            ends,
+            portals: self.linked_portals(),
+            heap: BinaryHeap::new(),
+            expanded: HashSet::new(),
+            came_from: HashMap::new(),
+        };
+        self.push_turns(&mut search, (start, None), 0.0);
+        self.turns = Some(search);
+    }
+    pub(crate) fn step_turn_search(&mut self) -> bool {
+        let mut search = match self.turns.take() {
+            Some(search) => search,
+            None => return false,
+        };
+
+        // approaches that were already expanded are skipped without using up
+        // a step
+        while let Some(Pending {
+            cost,
+            approach,
+            from,
+            ..
+        }) = search.heap.pop()
+        {
+            let ((x, y), _) = approach;
+
+            if self.get(x, y) == Some(&SquareKind::EndSquare) {
+                self.dist[y as usize][x as usize] = Some(cost);
+                search.came_from.insert(approach, from);
+                let path: Vec<(i32, i32)> =
+                    reconstruct_path(&search.came_from, (search.start, None), approach)
+                        .into_iter()
+                        .map(|(square, _)| square)
+                        .collect();
+                self.mark_solution(&path);
+                return false;
+            }
+
+            if !search.expanded.insert(approach) {
+                continue;
+            }
+            search.came_from.insert(approach, from);
+
+            self.record_expansion(x, y);
+            // the cheapest way in is what's shown, however many there are
+            let dist = &mut self.dist[y as usize][x as usize];
+            if !matches!(*dist, Some(best) if best <= cost) {
+                *dist = Some(cost);
+            }
+            self.mark_square(x, y, SquareKind::PossiblePath);
+            self.push_turns(&mut search, approach, cost);
+
+            self.turns = Some(search);
+            return true;
+        }
+
+        self.unsolvable = true;
+        false
+    }
+    fn push_turns(&mut self, search: &mut TurnSearch, from: Approach, cost: f64) {
+        let (parent, heading) = from;
+        for m in self.get_possible_moves(parent) {
+            let outgoing = self.heading(parent, &m, heading);
+            let turned = matches!((heading, outgoing), (Some(a), Some(b)) if a != b);
+            let cost = cost
+                + self.step_cost(&search.algorithm, parent, &m)
+                + if turned { self.turn_cost } else { 0.0 };
+            let priority = match search.algorithm {
+                Algorithm::AStar => {
+                    cost + self.heuristic((m.0, m.1), &search.ends, &search.portals)
+                }
+                _ => cost,
+            };
+
+            if self.get(m.0, m.1) == Some(&SquareKind::Init) {
+                self.mark_square(m.0, m.1, SquareKind::Frontier);
+            }
+            search.heap.push(Pending {
+                priority,
+                cost,
+                approach: ((m.0, m.1), outgoing),
+                from,
+            });
+        }
+    }
+    // which way a move goes, counting a step across a wrapped edge the way
+    // it actually goes rather than all the way back across the board. Jumping
+    // through a portal keeps the heading the search had going into it.
+    fn heading(&self, parent: (i32, i32), m: &Move, incoming: Heading) -> Heading {
+        if self.portal_partners(parent).contains(&(m.0, m.1)) {
+            return incoming;
+        }
+        let unwrap = |delta: i32| {
+            if delta.abs() > 1 {
+                -delta.signum()
+            } else {
+                delta
+            }
+        };
+        Some((unwrap(m.0 - parent.0), unwrap(m.1 - parent.1)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::path::path_to_directions;
+    use crate::{Algorithm, Heuristic, Maze};
+
+    #[test]
+    fn a_high_turn_cost_takes_a_straighter_route() {
+        let mut board = String::new();
+        for y in 0..12 {
+            for x in 0..12 {
+                board.push(match (x, y) {
+                    (0, 0) => 'S',
+                    (11, 11) => 'E',
+                    _ => ' ',
+                });
+            }
+            board.push('\n');
+        }
+
+        for algorithm in [Algorithm::Dijkstra, Algorithm::AStar].iter() {
+            let mut cheap = Maze::from_ascii(&board).unwrap();
+            cheap.heuristic = Heuristic::Zero;
+            let mut costly = cheap.clone();
+            costly.turn_cost = 5.0;
+
+            let cheap_path = cheap.run(algorithm.clone());
+            let costly_path = costly.run(algorithm.clone());
+            assert_eq!(costly_path.len(), cheap_path.len());
+            assert!(costly.is_valid_path(&costly_path));
+
+            // one turn is as few as a corner to corner route can take
+            let turns = |path: &[(i32, i32)]| path_to_directions(path).len() - 1;
+            assert_eq!(turns(&costly_path), 1, "{:?}", algorithm);
+            assert!(turns(&cheap_path) > 1, "{:?}", algorithm);
+        }
+    }
+}
@@ -0,0 +1,139 @@
+use crate::{Maze, SquareKind, MAX_DIMENSION, MIN_DIMENSION};
+use std::fmt;
+
+// what stops a board from being searched
+#[derive(Debug, PartialEq)]
+pub enum ValidationError {
+    MissingStart,
+    DuplicateStart { count: usize },
+    MissingEnd,
+}
+
+impl fmt::Display for ValidationError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ValidationError::MissingStart => write!(f, "the board has no start square"),
+            ValidationError::DuplicateStart { count } => {
+                write!(f, "the board has {} start squares", count)
+            }
+            ValidationError::MissingEnd => write!(f, "the board has no end square"),
+        }
+    }
+}
+
+impl std::error::Error for ValidationError {}
+
+// a board size asked for that's outside `MIN_DIMENSION` to `MAX_DIMENSION`
+// squares a side
+#[derive(Debug, PartialEq)]
+pub enum DimensionError {
+    TooSmall { height: i32, width: i32 },
+    TooLarge { height: i32, width: i32 },
+}
+
+impl fmt::Display for DimensionError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            DimensionError::TooSmall { height, width } => write!(
+                f,
+                "a {} by {} board is smaller than {} squares a side",
+                width, height, MIN_DIMENSION
+            ),
+            DimensionError::TooLarge { height, width } => write!(
+                f,
+                "a {} by {} board is larger than {} squares a side",
+                width, height, MAX_DIMENSION
+            ),
+        }
+    }
+}
+
+impl std::error::Error for DimensionError {}
+
+/// Checks a board of `height` by `width` squares is one the editor can
+/// make.
+pub fn check_dimensions(height: i32, width: i32) -> Result<(), DimensionError> {
+    if height < MIN_DIMENSION || width < MIN_DIMENSION {
+        Err(DimensionError::TooSmall { height, width })
+    } else if height > MAX_DIMENSION || width > MAX_DIMENSION {
+        Err(DimensionError::TooLarge { height, width })
+    } else {
+        Ok(())
+    }
+}
+
+impl Maze {
+    /// Checks the board has exactly one start square and at least one end
+    /// square, which is what every search needs.
+    pub fn validate(&self) -> Result<(), ValidationError> {
+        let count = |kind: SquareKind| {
+            self.squares
+                .iter()
+                .flatten()
+                .filter(|square_kind| **square_kind == kind)
+                .count()
+        };
+
+        match count(SquareKind::StartSquare) {
+            0 => return Err(ValidationError::MissingStart),
+            1 => {}
+            count => return Err(ValidationError::DuplicateStart { count }),
+        }
+        if count(SquareKind::EndSquare) == 0 {
+            return Err(ValidationError::MissingEnd);
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn a_board_needs_a_start() {
+        let mut maze = Maze::default();
+        maze.set(1, 5, SquareKind::Init);
+        assert_eq!(maze.validate(), Err(ValidationError::MissingStart));
+    }
+
+    #[test]
+    fn a_board_cant_have_two_starts() {
+        let mut maze = Maze::default();
+        maze.set(3, 3, SquareKind::StartSquare);
+        assert_eq!(
+            maze.validate(),
+            Err(ValidationError::DuplicateStart { count: 2 })
+        );
+    }
+
+    #[test]
+    fn a_board_needs_an_end() {
+        let mut maze = Maze::default();
+        maze.set(8, 5, SquareKind::Init);
+        assert_eq!(maze.validate(), Err(ValidationError::MissingEnd));
+
+        maze.add_end(2, 2);
+        maze.add_end(7, 7);
+        assert_eq!(maze.validate(), Ok(()));
+    }
+
+    #[test]
+    fn huge_dimensions_are_rejected() {
+        let mut maze = Maze::default();
+        assert_eq!(
+            maze.try_resize(2_000_000, 2_000_000),
+            Err(DimensionError::TooLarge {
+                height: 2_000_000,
+                width: 2_000_000
+            })
+        );
+        assert_eq!(
+            (maze.height, maze.width),
+            (crate::DEFAULT_HEIGHT, crate::DEFAULT_WIDTH)
+        );
+
+        assert!(maze.try_resize(20, 30).is_ok());
+        assert_eq!(maze.square_count(), 600);
+    }
+}
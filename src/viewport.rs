@@ -0,0 +1,280 @@
+use druid::widget::prelude::*;
+use druid::{Color, Point, Rect, Vec2, WidgetPod};
+
+// how far in the board can be zoomed, and how much one turn of the wheel
+// zooms it by
+const MIN_ZOOM: f64 = 1.0;
+const MAX_ZOOM: f64 = 8.0;
+const ZOOM_STEP: f64 = 1.25;
+
+// the longest side of the minimap and its gap from the corner, in pixels
+const MINIMAP_SIZE: f64 = 120.0;
+const MINIMAP_MARGIN: f64 = 8.0;
+const MINIMAP_FRAME: Color = Color::rgb8(0x40, 0x40, 0x40);
+const MINIMAP_VIEW: Color = Color::rgb8(0xe0, 0x30, 0x30);
+
+// draws the whole board shrunk down into the given rectangle
+type DrawBoard<T> = Box<dyn Fn(&mut PaintCtx, &T, Rect)>;
+
+// shows its child scaled up by `zoom` and shifted by `pan`, which the mouse
+// wheel and dragging with the middle button change. The child is laid out at
+// its zoomed size, so clicks land on whichever square is under the cursor
+// without any extra bookkeeping.
+pub struct Viewport<T, W> {
+    child: WidgetPod<T, W>,
+    zoom: f64,
+    pan: Vec2,
+    // where the mouse was when the middle button drag last moved
+    drag: Option<Point>,
+    minimap: Option<DrawBoard<T>>,
+    // whether the left button went down on the minimap and is still held
+    steering: bool,
+}
+
+impl<T: Data, W: Widget<T>> Viewport<T, W> {
+    pub fn new(child: W) -> Viewport<T, W> {
+        Viewport {
+            child: WidgetPod::new(child),
+            zoom: MIN_ZOOM,
+            pan: Vec2::ZERO,
+            drag: None,
+            minimap: None,
+            steering: false,
+        }
+    }
+    // shows the whole board in the bottom right corner while zoomed in, with
+    // the part in view outlined. Clicking or dragging on it moves the view.
+    pub fn with_minimap(mut self, draw: impl Fn(&mut PaintCtx, &T, Rect) + 'static) -> Self {
+        self.minimap = Some(Box::new(draw));
+        self
+    }
+    fn minimap_rect(&self, size: Size) -> Option<Rect> {
+        if self.minimap.is_none() || self.zoom <= MIN_ZOOM {
+            return None;
+        }
+        Some(minimap_rect(size))
+    }
+}
+
+impl<T: Data, W: Widget<T>> Widget<T> for Viewport<T, W> {
+    fn event(&mut self, ctx: &mut EventCtx, event: &Event, data: &mut T, env: &Env) {
+        // a drag that starts on the minimap moves the view instead of
+        // reaching the board
+        match event {
+            Event::MouseUp(mouse) if mouse.button.is_left() && self.steering => {
+                self.steering = false;
+                ctx.set_active(false);
+                ctx.set_handled();
+                return;
+            }
+            _ => {}
+        }
+        if let Some(minimap) = self.minimap_rect(ctx.size()) {
+            let steer = match event {
+                Event::MouseDown(mouse)
+                    if mouse.button.is_left() && minimap.contains(mouse.pos) =>
+                {
+                    self.steering = true;
+                    ctx.set_active(true);
+                    Some(mouse.pos)
+                }
+                Event::MouseMove(mouse) if self.steering => Some(mouse.pos),
+                _ => None,
+            };
+            if let Some(pos) = steer {
+                self.pan = recentre(pos, minimap, ctx.size(), self.zoom);
+                ctx.request_layout();
+                ctx.set_handled();
+                return;
+            }
+        }
+
+        match event {
+            Event::Wheel(mouse) => {
+                let zoom = if mouse.wheel_delta.y < 0.0 {
+                    self.zoom * ZOOM_STEP
+                } else {
+                    self.zoom / ZOOM_STEP
+                };
+                let zoom = zoom.clamp(MIN_ZOOM, MAX_ZOOM);
+                let pan = zoom_about(self.pan, mouse.pos, self.zoom, zoom);
+
+                self.zoom = zoom;
+                self.pan = clamp_pan(pan, ctx.size(), zoom);
+                ctx.request_layout();
+                ctx.set_handled();
+                return;
+            }
+            Event::MouseDown(mouse) if mouse.button.is_middle() => {
+                self.drag = Some(mouse.pos);
+                ctx.set_active(true);
+                ctx.set_handled();
+                return;
+            }
+            Event::MouseMove(mouse) => {
+                if let Some(last) = self.drag {
+                    self.pan = clamp_pan(self.pan - (mouse.pos - last), ctx.size(), self.zoom);
+                    self.drag = Some(mouse.pos);
+                    ctx.request_layout();
+                    ctx.set_handled();
+                    return;
+                }
+            }
+            Event::MouseUp(mouse) if mouse.button.is_middle() => {
+                self.drag = None;
+                ctx.set_active(false);
+                ctx.set_handled();
+                return;
+            }
+            _ => {}
+        }
+
+        self.child.event(ctx, event, data, env)
+    }
+
+    fn lifecycle(&mut self, ctx: &mut LifeCycleCtx, event: &LifeCycle, data: &T, env: &Env) {
+        self.child.lifecycle(ctx, event, data, env)
+    }
+
+    fn update(&mut self, ctx: &mut UpdateCtx, old_data: &T, data: &T, env: &Env) {
+        // the board only repaints the squares that changed, which may not be
+        // the ones under the minimap
+        if self.minimap_rect(ctx.size()).is_some() && !old_data.same(data) {
+            ctx.request_paint();
+        }
+        self.child.update(ctx, data, env)
+    }
+
+    fn layout(&mut self, ctx: &mut LayoutCtx, bc: &BoxConstraints, data: &T, env: &Env) -> Size {
+        let size = bc.max();
+
+        // the window may have shrunk since the board was last panned
+        self.pan = clamp_pan(self.pan, size, self.zoom);
+        self.child
+            .layout(ctx, &BoxConstraints::tight(size * self.zoom), data, env);
+        self.child.set_origin(ctx, Point::ORIGIN - self.pan);
+        size
+    }
+
+    fn paint(&mut self, ctx: &mut PaintCtx, data: &T, env: &Env) {
+        let bounds = ctx.size().to_rect();
+        let child = &mut self.child;
+        ctx.with_save(|ctx| {
+            ctx.clip(bounds);
+            child.paint(ctx, data, env);
+        });
+
+        let (minimap, draw) = match (self.minimap_rect(bounds.size()), &self.minimap) {
+            (Some(minimap), Some(draw)) => (minimap, draw),
+            _ => return,
+        };
+        draw(ctx, data, minimap);
+        ctx.stroke(minimap, &MINIMAP_FRAME, 1.0);
+        ctx.stroke(
+            view_rect(self.pan, minimap, bounds.size(), self.zoom),
+            &MINIMAP_VIEW,
+            2.0,
+        );
+    }
+}
+
+// where the minimap goes in a viewport of `size`, shaped like the board and
+// never more than a third of the viewport across
+fn minimap_rect(size: Size) -> Rect {
+    let longest = MINIMAP_SIZE.min(size.width.max(size.height) / 3.0);
+    let scale = longest / size.width.max(size.height);
+    let minimap = Size::new(size.width * scale, size.height * scale);
+    let origin = Point::new(
+        size.width - minimap.width - MINIMAP_MARGIN,
+        size.height - minimap.height - MINIMAP_MARGIN,
+    );
+    Rect::from_origin_size(origin, minimap)
+}
+
+// the part of the minimap that's in view with the board zoomed and panned
+fn view_rect(pan: Vec2, minimap: Rect, size: Size, zoom: f64) -> Rect {
+    let origin = Point::new(
+        minimap.x0 + pan.x / (size.width * zoom) * minimap.width(),
+        minimap.y0 + pan.y / (size.height * zoom) * minimap.height(),
+    );
+    Rect::from_origin_size(
+        origin,
+        Size::new(minimap.width() / zoom, minimap.height() / zoom),
+    )
+}
+
+// the pan that centres the view on whatever is under `click` on the minimap
+fn recentre(click: Point, minimap: Rect, size: Size, zoom: f64) -> Vec2 {
+    let target = Vec2::new(
+        (click.x - minimap.x0) / minimap.width() * size.width * zoom,
+        (click.y - minimap.y0) / minimap.height() * size.height * zoom,
+    );
+    clamp_pan(target - size.to_vec2() / 2.0, size, zoom)
+}
+
+// the pan that keeps the point under `cursor` in the same place on screen
+// when the zoom changes from `old` to `new`
+fn zoom_about(pan: Vec2, cursor: Point, old: f64, new: f64) -> Vec2 {
+    (pan + cursor.to_vec2()) * (new / old) - cursor.to_vec2()
+}
+
+// keeps the zoomed board covering the whole of a viewport of `size`
+fn clamp_pan(pan: Vec2, size: Size, zoom: f64) -> Vec2 {
+    let overhang = size.to_vec2() * (zoom - 1.0);
+    Vec2::new(pan.x.clamp(0.0, overhang.x), pan.y.clamp(0.0, overhang.y))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // where on the unzoomed board the point at `screen` in the viewport is
+    fn board_point(screen: Point, pan: Vec2, zoom: f64) -> Point {
+        ((screen.to_vec2() + pan) / zoom).to_point()
+    }
+
+    #[test]
+    fn zooming_keeps_the_square_under_the_cursor_in_place() {
+        let cursor = Point::new(120.0, 45.0);
+        let pan = Vec2::new(30.0, 10.0);
+        let before = board_point(cursor, pan, 2.0);
+
+        let pan = zoom_about(pan, cursor, 2.0, 2.5);
+        let after = board_point(cursor, pan, 2.5);
+        assert!((before - after).hypot() < 1e-9);
+        assert!((after - Point::new(75.0, 27.5)).hypot() < 1e-9);
+    }
+
+    #[test]
+    fn pan_stays_on_the_board() {
+        let size = Size::new(200.0, 100.0);
+        assert_eq!(
+            clamp_pan(Vec2::new(-5.0, 40.0), size, 2.0),
+            Vec2::new(0.0, 40.0)
+        );
+        assert_eq!(
+            clamp_pan(Vec2::new(500.0, 500.0), size, 2.0),
+            Vec2::new(200.0, 100.0)
+        );
+        assert_eq!(clamp_pan(Vec2::new(10.0, 10.0), size, MIN_ZOOM), Vec2::ZERO);
+    }
+
+    #[test]
+    fn clicking_the_minimap_centres_the_view_on_that_spot() {
+        let size = Size::new(400.0, 200.0);
+        let minimap = minimap_rect(size);
+        assert_eq!(minimap.size(), Size::new(120.0, 60.0));
+
+        // the middle of the minimap is the middle of the board
+        let pan = recentre(minimap.center(), minimap, size, 3.0);
+        assert_eq!(pan, Vec2::new(400.0, 200.0));
+        assert_eq!(
+            view_rect(pan, minimap, size, 3.0).center(),
+            minimap.center()
+        );
+
+        // a click near a corner pulls the view back onto the board
+        let pan = recentre(minimap.origin(), minimap, size, 3.0);
+        assert_eq!(pan, Vec2::ZERO);
+    }
+}
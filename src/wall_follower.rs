@@ -0,0 +1,116 @@
+use crate::{Algorithm, Direction, Maze, SquareKind};
+
+// the step a walker takes facing `facing`, an index into the straight
+// directions, which go clockwise so turning right is a step forwards
+fn step(facing: usize) -> (i32, i32) {
+    Direction::ALL[facing].delta()
+}
+
+// a walker keeping its right hand on the wall, moving one square per step
+#[derive(Clone)]
+pub(crate) struct WallFollower {
+    position: (i32, i32),
+    facing: usize,
+    route: Vec<(i32, i32)>,
+    steps_left: usize,
+}
+
+impl Maze {
+    /// Traces the route taken by keeping a right hand on the wall. This only
+    /// reaches the end when the walls around it connect back to the walls
+    /// around the start; otherwise the walker can circle forever, so it gives
+    /// up after `width * height * 4` steps.
+    pub fn traverse_wall_follower(&mut self) -> Vec<(i32, i32)> {
+        self.run(Algorithm::WallFollower)
+    }
+    pub(crate) fn begin_wall_follower(&mut self, start: (i32, i32)) {
+        self.wall_follower = Some(WallFollower {
+            position: start,
+            facing: 0,
+            route: vec![start],
+            steps_left: self.square_count().saturating_mul(4),
+        });
+    }
+    pub(crate) fn step_wall_follower(&mut self) -> bool {
+        let mut walker = match self.wall_follower.take() {
+            Some(walker) => walker,
+            None => return false,
+        };
+
+        if walker.steps_left == 0 {
+            self.unsolvable = true;
+            return false;
+        }
+        walker.steps_left -= 1;
+
+        // try turning right, then straight on, then left, then going back
+        let (x, y) = walker.position;
+        let facing = [1, 0, 3, 2]
+            .iter()
+            .map(|turn| (walker.facing + turn) % 4)
+            .find(|&facing| {
+                let (dx, dy) = step(facing);
+                self.is_open(x + dx, y + dy)
+            });
+        let facing = match facing {
+            Some(facing) => facing,
+            None => {
+                // walled in on every side
+                self.unsolvable = true;
+                return false;
+            }
+        };
+
+        let (dx, dy) = step(facing);
+        let (x, y) = self.wrapped(x + dx, y + dy);
+        walker.facing = facing;
+        walker.route.push((x, y));
+        self.record_expansion(x, y);
+
+        // stepping onto a portal carries the walker through to the other
+        // side, still facing the same way
+        let (x, y) = match self.portal_partners((x, y)).first() {
+            Some(&(x, y)) => {
+                walker.route.push((x, y));
+                self.record_expansion(x, y);
+                (x, y)
+            }
+            None => (x, y),
+        };
+        walker.position = (x, y);
+
+        if self.get(x, y) == Some(&SquareKind::EndSquare) {
+            self.mark_solution(&walker.route);
+            return false;
+        }
+
+        self.mark_square(x, y, SquareKind::PossiblePath);
+        self.wall_follower = Some(walker);
+        true
+    }
+    // the walker can step anywhere on the board that isn't a wall, including
+    // back over the start
+    fn is_open(&self, x: i32, y: i32) -> bool {
+        let (x, y) = self.wrapped(x, y);
+        y >= 0 && y < self.height && x >= 0 && x < self.width && !self.is_obstacle(x, y)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Maze, MazeGenerator};
+
+    #[test]
+    fn follows_the_wall_to_the_end_of_a_perfect_maze() {
+        for seed in 0..10 {
+            for &(height, width) in &[(9, 10), (21, 21), (15, 31)] {
+                let mut maze = Maze::new(height, width);
+                maze.generate(&MazeGenerator::Backtracker, Some(seed));
+
+                let path = maze.traverse_wall_follower();
+                assert!(maze.solved, "seed {}", seed);
+                assert!(maze.is_valid_path(&path), "seed {}", seed);
+            }
+        }
+    }
+}
@@ -0,0 +1,93 @@
+use druid::widget::prelude::*;
+use druid::widget::Controller;
+use druid::{Screen, Selector};
+
+use crate::{State, BOARD_FLEX, SPACER_FLEX};
+
+pub const FIT_WINDOW: Selector = Selector::new("pollen.fit-window");
+
+// how wide each square is drawn once the window has been fitted to the board
+const CELL_PX: f64 = 24.0;
+
+// the room the board takes up on screen when each square is `cell_px` across
+fn ideal_window_size(width: i32, height: i32, cell_px: f64) -> (f64, f64) {
+    (
+        width.max(1) as f64 * cell_px,
+        height.max(1) as f64 * cell_px,
+    )
+}
+
+// the window size that gives the board `ideal` room, given how big the window
+// and the board's share of it are now, no bigger than `work_area`. The board
+// is as wide as the window, but only gets its flex share of any height added
+// to it, the rest going to the spacer under the status lines.
+fn fitted_window(window: Size, board: Size, ideal: (f64, f64), work_area: Size) -> Size {
+    let share = BOARD_FLEX / (BOARD_FLEX + SPACER_FLEX);
+    let width = window.width + ideal.0 - board.width;
+    let height = window.height + (ideal.1 - board.height) / share;
+    Size::new(
+        width.min(work_area.width).max(1.0),
+        height.min(work_area.height).max(1.0),
+    )
+}
+
+// resizes the window around the board it wraps on `FIT_WINDOW`, so that each
+// square comes out `CELL_PX` across, or as near as the screen allows
+pub struct FitWindow;
+
+impl<W: Widget<State>> Controller<State, W> for FitWindow {
+    fn event(
+        &mut self,
+        child: &mut W,
+        ctx: &mut EventCtx,
+        event: &Event,
+        data: &mut State,
+        env: &Env,
+    ) {
+        if let Event::Command(cmd) = event {
+            if cmd.is(FIT_WINDOW) {
+                let ideal = ideal_window_size(data.maze.width, data.maze.height, CELL_PX);
+                let window = ctx.window().get_size();
+                // without a screen to measure against, the window is only
+                // ever as big as it was asked to be
+                let work_area = Screen::get_monitors()
+                    .into_iter()
+                    .find(|monitor| monitor.is_primary())
+                    .map(|monitor| monitor.virtual_work_rect().size())
+                    .unwrap_or(Size::new(f64::INFINITY, f64::INFINITY));
+                let size = fitted_window(window, ctx.size(), ideal, work_area);
+                ctx.window().set_size(size);
+                return;
+            }
+        }
+
+        child.event(ctx, event, data, env)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn the_ideal_window_fits_each_square_at_the_cell_size() {
+        assert_eq!(ideal_window_size(10, 9, 24.0), (240.0, 216.0));
+        assert_eq!(ideal_window_size(100, 3, 10.0), (1000.0, 30.0));
+        assert_eq!(ideal_window_size(0, 0, 24.0), (24.0, 24.0));
+    }
+
+    #[test]
+    fn a_fitted_window_grows_by_what_the_board_is_short_of() {
+        let window = Size::new(500.0, 700.0);
+        let board = Size::new(500.0, 400.0);
+        let unlimited = Size::new(f64::INFINITY, f64::INFINITY);
+        let share = BOARD_FLEX / (BOARD_FLEX + SPACER_FLEX);
+
+        let fitted = fitted_window(window, board, (600.0, 500.0), unlimited);
+        assert_eq!(fitted, Size::new(600.0, 700.0 + 100.0 / share));
+
+        // never past the edge of the screen
+        let screen = Size::new(550.0, 750.0);
+        assert_eq!(fitted_window(window, board, (600.0, 500.0), screen), screen);
+    }
+}
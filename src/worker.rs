@@ -0,0 +1,252 @@
+use druid::{ExtEventSink, Selector, Target};
+use pollen::{Algorithm, Maze, MazeGenerator};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::thread;
+use std::time::{Duration, Instant};
+
+// how many squares the worker expands between progress reports
+const PROGRESS_STEPS: usize = 500;
+// how much more of a maze has to be carved before it's worth reporting
+const PROGRESS_SHARE: f64 = 0.01;
+
+pub const SOLVE_PROGRESS: Selector<Progress> = Selector::new("pollen.solve-progress");
+pub const SOLVE_DONE: Selector<Solved> = Selector::new("pollen.solve-done");
+pub const GENERATE_PROGRESS: Selector<Carving> = Selector::new("pollen.generate-progress");
+pub const GENERATE_DONE: Selector<Generated> = Selector::new("pollen.generate-done");
+
+// set to ask a worker to stop, and kept in `State` while it runs so that
+// reports from a run that has since been cancelled or replaced can be told
+// apart from the current one
+pub type Run = Arc<AtomicBool>;
+
+pub struct Progress {
+    pub run: Run,
+    pub explored: usize,
+}
+
+pub struct Solved {
+    pub run: Run,
+    // the board the search started from. The result only belongs on the
+    // window if the board is still this one, since any edit since copies it.
+    pub source: Arc<Maze>,
+    // the board as the search left it, solved or as far as it got before
+    // being cancelled
+    pub maze: Arc<Maze>,
+    pub algorithm: Algorithm,
+    pub steps: usize,
+    pub elapsed: Duration,
+}
+
+pub struct Carving {
+    pub run: Run,
+    // the share of the maze carved so far
+    pub carved: f64,
+}
+
+pub struct Generated {
+    pub run: Run,
+    // the board the maze was carved from, as for `Solved`
+    pub source: Arc<Maze>,
+    pub maze: Arc<Maze>,
+}
+
+// what a worker sends back to the window
+enum Report {
+    Progress(Progress),
+    Done(Solved),
+}
+
+// what a generating worker sends back to the window
+pub enum GenerateReport {
+    Progress(Carving),
+    Done(Generated),
+}
+
+// solves a copy of `source` on its own thread, reporting how far it has got
+// every so often and the finished board at the end
+pub fn spawn(sink: ExtEventSink, source: Arc<Maze>, algorithm: Algorithm) -> Run {
+    spawn_with(source, algorithm, move |report| {
+        let sent = match report {
+            Report::Progress(progress) => {
+                sink.submit_command(SOLVE_PROGRESS, progress, Target::Auto)
+            }
+            Report::Done(solved) => sink.submit_command(SOLVE_DONE, solved, Target::Auto),
+        };
+        sent.is_ok()
+    })
+}
+
+// `spawn`, handing each report to `post`, which says whether anyone is still
+// listening for more
+fn spawn_with(
+    source: Arc<Maze>,
+    algorithm: Algorithm,
+    mut post: impl FnMut(Report) -> bool + Send + 'static,
+) -> Run {
+    let run = Run::default();
+    let cancelled = run.clone();
+
+    thread::spawn(move || {
+        let started = Instant::now();
+        let mut maze = (*source).clone();
+        let mut steps = 0;
+        if maze.begin_search(algorithm.clone()) {
+            loop {
+                steps += 1;
+                if !maze.step() {
+                    break;
+                }
+                if cancelled.load(Ordering::Relaxed) {
+                    maze.cancel_search();
+                    break;
+                }
+
+                if steps % PROGRESS_STEPS == 0 {
+                    let progress = Progress {
+                        run: cancelled.clone(),
+                        explored: maze.last_explored,
+                    };
+                    // the window has closed, nobody is waiting for the result
+                    if !post(Report::Progress(progress)) {
+                        return;
+                    }
+                }
+            }
+        }
+
+        post(Report::Done(Solved {
+            run: cancelled,
+            source,
+            maze: Arc::new(maze),
+            algorithm,
+            steps,
+            elapsed: started.elapsed(),
+        }));
+    });
+
+    run
+}
+
+// carves a maze on a copy of `source` on its own thread, reporting how much
+// of it is carved every so often and the finished board at the end
+pub fn spawn_generation(
+    sink: ExtEventSink,
+    source: Arc<Maze>,
+    generator: MazeGenerator,
+    seed: Option<u64>,
+) -> Run {
+    generate_with(source, generator, seed, move |report| {
+        let sent = match report {
+            GenerateReport::Progress(carving) => {
+                sink.submit_command(GENERATE_PROGRESS, carving, Target::Auto)
+            }
+            GenerateReport::Done(generated) => {
+                sink.submit_command(GENERATE_DONE, generated, Target::Auto)
+            }
+        };
+        sent.is_ok()
+    })
+}
+
+// `spawn_generation`, handing each report to `post`. A generator can't be
+// stopped part way, so a cancelled run carves on but sends nothing more.
+pub fn generate_with(
+    source: Arc<Maze>,
+    generator: MazeGenerator,
+    seed: Option<u64>,
+    mut post: impl FnMut(GenerateReport) -> bool + Send + 'static,
+) -> Run {
+    let run = Run::default();
+    let cancelled = run.clone();
+
+    thread::spawn(move || {
+        let mut maze = (*source).clone();
+        let mut reported = 0.0;
+        let mut listening = true;
+        maze.generate_with_progress(&generator, seed, &mut |carved| {
+            if cancelled.load(Ordering::Relaxed) {
+                listening = false;
+            }
+            if listening && carved - reported >= PROGRESS_SHARE {
+                reported = carved;
+                let run = cancelled.clone();
+                listening = post(GenerateReport::Progress(Carving { run, carved }));
+            }
+        });
+
+        if listening {
+            post(GenerateReport::Done(Generated {
+                run: cancelled,
+                source,
+                maze: Arc::new(maze),
+            }));
+        }
+    });
+
+    run
+}
+
+pub fn cancel(run: &Run) {
+    run.store(true, Ordering::Relaxed);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pollen::SquareKind;
+    use std::sync::mpsc;
+
+    #[test]
+    fn a_large_board_is_solved_off_the_main_thread() {
+        let mut maze = Maze::new(500, 500);
+        maze.move_endpoint(499, 499, SquareKind::EndSquare);
+        let (sender, reports) = mpsc::channel();
+        spawn_with(Arc::new(maze), Algorithm::Bfs, move |report| {
+            sender.send(report).is_ok()
+        });
+
+        let mut explored = 0;
+        loop {
+            match reports.recv_timeout(Duration::from_secs(60)).unwrap() {
+                Report::Progress(progress) => {
+                    assert!(progress.explored > explored);
+                    explored = progress.explored;
+                }
+                Report::Done(solved) => {
+                    assert!(solved.maze.solved);
+                    assert!(explored > 0);
+                    break;
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn a_large_board_is_generated_off_the_main_thread() {
+        let (sender, reports) = mpsc::channel();
+        let source = Arc::new(Maze::new(300, 300));
+        generate_with(
+            source.clone(),
+            MazeGenerator::Prim,
+            Some(1),
+            move |report| sender.send(report).is_ok(),
+        );
+
+        let mut carved = 0.0;
+        loop {
+            match reports.recv_timeout(Duration::from_secs(60)).unwrap() {
+                GenerateReport::Progress(carving) => {
+                    assert!(carving.carved > carved);
+                    carved = carving.carved;
+                }
+                GenerateReport::Done(generated) => {
+                    assert!(Arc::ptr_eq(&generated.source, &source));
+                    assert_ne!(generated.maze.squares, source.squares);
+                    assert!(carved > 0.9);
+                    break;
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,34 @@
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+
+// runs the pollen binary headless on one of the bench fixtures
+fn solve(fixture: &str, algorithm: &str) -> std::process::Output {
+    let maze = Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("benches/fixtures")
+        .join(fixture);
+    Command::new(env!("CARGO_BIN_EXE_pollen"))
+        .args(["solve", maze.to_str().unwrap(), "--algo", algorithm])
+        .output()
+        .expect("pollen runs")
+}
+
+#[test]
+fn solves_a_fixture_and_prints_the_board() {
+    let output = solve("open.txt", "bfs");
+    assert!(output.status.success());
+
+    let expected = fs::read_to_string(
+        Path::new(env!("CARGO_MANIFEST_DIR")).join("benches/fixtures/solved/open.bfs.txt"),
+    )
+    .unwrap();
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert!(stdout.starts_with(&expected));
+    assert!(stdout.contains("path length: 40\n"));
+}
+
+#[test]
+fn rejects_an_unknown_algorithm() {
+    let output = solve("open.txt", "teleport");
+    assert_eq!(output.status.code(), Some(2));
+}